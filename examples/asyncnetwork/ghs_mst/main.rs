@@ -0,0 +1,31 @@
+use d2simrs::*;
+use d2simrs::algorithms::mst::ghs::{GhsBuilder, GhsMessage};
+use d2simrs::algorithms::mst::verify_mst;
+use d2simrs::topo::TOPOLOGY;
+use d2simrs::topo::anchoredrandomgraph::AnchoredRandomGraphGen;
+use d2simrs::topo::topogen::TopologyGenerator;
+
+fn main() {
+	println!("GHS Minimum Spanning Tree in Asynchronous Networks");
+
+	let mut simulation = Simulation::<DelayChannel>::default();
+	let mut builder = GhsBuilder::new(DelayChannelBuilder::with_delay(std::time::Duration::from_millis(10)));
+
+	const NUM_NODES: usize = 20;
+	let gen = AnchoredRandomGraphGen::new(NUM_NODES, 0.2);
+	let mut topo = TopologyGenerator::new(NUM_NODES, gen).try_build_connected_network();
+
+	simulation.build_from_topo(&mut topo, &mut builder);
+	builder.set_weights(&topo);
+	simulation.register_message::<GhsMessage>("ghs");
+
+	simulation.call_init();
+	simulation.run().unwrap();
+	simulation.call_terminate();
+
+	let tree = builder.tree();
+	let weight = verify_mst(&topo, &tree.borrow().branches).unwrap();
+	let links = simulation.env().get(TOPOLOGY).unwrap().links.len();
+	println!("\nminimum spanning tree: {} of {} edges, weight {:.3}", tree.borrow().branches.len(), links, weight);
+	print!("{}", simulation.complexity());
+}
@@ -0,0 +1,127 @@
+use std::any::Any;
+use std::time::{Duration, Instant};
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use d2simrs::*;
+use d2simrs::dummycomponent::DummyComponentBuilder;
+use d2simrs::scheduler::{EventListKind, EventType, Scheduler};
+
+const KINDS: [EventListKind; 3] = [EventListKind::BinaryHeap, EventListKind::Calendar, EventListKind::Ladder];
+
+/// delay of the next event, uniform or exponential with a mean of 1ms
+#[derive(Debug, Copy, Clone)]
+enum Increment {
+	Uniform,
+	Exponential,
+}
+
+impl Increment {
+	fn sample(&self, rng: &mut StdRng) -> SimTimeDelta {
+		let nanos = match self {
+			Increment::Uniform => rng.gen_range(0.0..2e6),
+			Increment::Exponential => -(1.0 - rng.gen::<f64>()).ln() * 1e6,
+		};
+		SimTimeDelta::from(Duration::from_nanos(nanos as u64))
+	}
+}
+
+/// classic hold model: `pending` events stay on the list, each event taken schedules a new one
+fn hold(kind: EventListKind, increment: Increment, pending: usize, holds: usize) -> f64 {
+	let mut rng = StdRng::seed_from_u64(1);
+	let mut sched = Scheduler::new();
+	sched.set_event_list(kind);
+	// the events only need a receiver, nothing processes them
+	let component = Simulation::<DelayChannel>::default().add_component(&mut DummyComponentBuilder::default());
+
+	for _ in 0..pending {
+		sched.sched_self_event(increment.sample(&mut rng), component);
+	}
+
+	let start = Instant::now();
+	for _ in 0..holds {
+		assert!(matches!(sched.next_event(), EventType::ProcessEvent(_)));
+		sched.sched_self_event(increment.sample(&mut rng), component);
+	}
+	holds as f64 / start.elapsed().as_secs_f64()
+}
+
+/// keeps `timers` self events pending until `events` were processed
+struct Ticker {
+	id: ComponentId,
+	timers: usize,
+	events: usize,
+	rng: StdRng,
+}
+
+impl Component for Ticker {
+	fn sim_id(&self) -> ComponentId {
+		self.id
+	}
+
+	fn add_channel(&mut self, _channel_id: ChannelId, _label: ChannelLabel) {}
+
+	fn init(&mut self) {
+		for _ in 0..self.timers {
+			let delay = Increment::Exponential.sample(&mut self.rng);
+			sim_sched().sched_self_event(delay, self.id);
+		}
+	}
+
+	fn process_event(&mut self, _sender: ComponentId, _event: Box<dyn Any>) {
+		if self.events > 0 {
+			self.events -= 1;
+			let delay = Increment::Exponential.sample(&mut self.rng);
+			sim_sched().sched_self_event(delay, self.id);
+		}
+	}
+
+	fn receive_msg(&mut self, _incoming_channel: ChannelId, _msg: Box<dyn Any>) {}
+
+	fn terminate(&mut self) {}
+}
+
+struct TickerBuilder {
+	timers: usize,
+	events: usize,
+}
+
+impl ComponentBuilder for TickerBuilder {
+	fn build_component(&mut self, id: ComponentId) -> Box<dyn Component> {
+		Box::new(Ticker { id, timers: self.timers, events: self.events, rng: StdRng::seed_from_u64(id.as_idx() as u64) })
+	}
+}
+
+/// events per second of a whole simulation of components with many pending timers
+fn simulation(kind: EventListKind, components: usize, timers: usize, events: usize) -> f64 {
+	let mut simulation = Simulation::<DelayChannel>::with_event_list(kind);
+	let mut builder = TickerBuilder { timers, events: events / components };
+	for _ in 0..components {
+		simulation.add_component(&mut builder);
+	}
+
+	simulation.call_init();
+	let start = Instant::now();
+	simulation.run().unwrap();
+	(components * (timers + events / components)) as f64 / start.elapsed().as_secs_f64()
+}
+
+fn main() {
+	println!("Throughput of the future event lists, build with --release for meaningful numbers");
+
+	const HOLDS: usize = 1_000_000;
+	for increment in [Increment::Uniform, Increment::Exponential] {
+		for pending in [1_000, 100_000, 1_000_000] {
+			for kind in KINDS {
+				let rate = hold(kind, increment, pending, HOLDS);
+				println!("hold {:<11?} {:>9} pending {:>12?}: {:>12.0} events/s", increment, pending, kind, rate);
+			}
+		}
+	}
+
+	let rates: Vec<f64> = KINDS.iter().map(|kind| simulation(*kind, 100, 1_000, 1_000_000)).collect();
+	for (kind, rate) in KINDS.iter().zip(rates) {
+		println!("simulation 100 components x 1000 timers {:>12?}: {:>12.0} events/s", kind, rate);
+	}
+}
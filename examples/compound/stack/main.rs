@@ -0,0 +1,124 @@
+use std::time::Duration;
+
+use d2simrs::*;
+use d2simrs::asynch::process::{AsynchComponent, AsynchProcess};
+use d2simrs::compound::{CompoundBuilder, Module};
+
+#[derive(Debug, Clone)]
+enum Message {
+	Ping(u32),
+	Pong(u32),
+}
+
+// application layer -------------------
+
+/// sends pings to the application on the other host and answers its pings
+struct App {
+	base: ComponentBase,
+	initiator: bool,
+}
+
+impl AsynchProcess for App {
+	type Msg = Message;
+
+	fn get_sim_base(&self) -> &ComponentBase { &self.base }
+
+	fn get_sim_base_mut(&mut self) -> &mut ComponentBase { &mut self.base }
+
+	fn on_start(&mut self) {
+		if self.initiator {
+			self.send_on("down", Message::Ping(0));
+		}
+	}
+
+	fn on_message(&mut self, incoming_channel: ChannelId, msg: Message) {
+		sim_log!(self.id(), "received {:?}", msg);
+		match msg {
+			Message::Ping(seq) => self.send(incoming_channel, Message::Pong(seq)),
+			Message::Pong(seq) if seq < 2 => self.send(incoming_channel, Message::Ping(seq + 1)),
+			Message::Pong(_) => {}
+		}
+	}
+
+	fn terminate(&mut self) {}
+}
+
+// network interface -------------------
+
+/// passes messages between the application and the wire
+struct Nic {
+	base: ComponentBase,
+}
+
+impl AsynchProcess for Nic {
+	type Msg = Message;
+
+	fn get_sim_base(&self) -> &ComponentBase { &self.base }
+
+	fn get_sim_base_mut(&mut self) -> &mut ComponentBase { &mut self.base }
+
+	fn on_start(&mut self) {}
+
+	fn on_message(&mut self, incoming_channel: ChannelId, msg: Message) {
+		let out = if self.gates().channel("up") == Some(incoming_channel) { "wire" } else { "up" };
+		self.send_on(out, msg);
+	}
+
+	fn terminate(&mut self) {}
+}
+
+// host -------------------
+
+struct HostBuilder {
+	initiator: bool,
+}
+
+impl ComponentBuilder for HostBuilder {
+	fn build_component(&mut self, id: ComponentId) -> Box<dyn Component> {
+		let mut base = ComponentBase::new(id);
+		base.gates.declare("down");
+		Box::new(AsynchComponent::new(App { base, initiator: self.initiator }))
+	}
+}
+
+struct NicBuilder;
+
+impl ComponentBuilder for NicBuilder {
+	fn build_component(&mut self, id: ComponentId) -> Box<dyn Component> {
+		let mut base = ComponentBase::new(id);
+		base.gates.declare("up").declare("wire");
+		Box::new(AsynchComponent::new(Nic { base }))
+	}
+}
+
+impl CompoundBuilder for HostBuilder {
+	fn build_compound(&mut self, module: &mut Module) {
+		let app = module.add_submodule("app", self);
+		let nic = module.add_submodule("nic", &mut NicBuilder);
+		module.connect(app.gate("down"), nic.gate("up"));
+		module.set_gate(nic);
+	}
+}
+
+fn main() {
+	println!("Hosts built from application and network interface submodules");
+
+	let mut simulation = Simulation::<DelayChannel>::default();
+	simulation.enable_tracing();
+
+	let host0 = simulation.add_compound("host0", &mut HostBuilder { initiator: true });
+	let host1 = simulation.add_compound("host1", &mut HostBuilder { initiator: false });
+	assert_eq!(simulation.lookup("host1.nic"), Some(host1.gate));
+	simulation.connect(host0.gate.gate("wire"), host1.gate.gate("wire"),
+	                   &mut DelayChannelBuilder::with_delay(Duration::from_millis(10)));
+
+	simulation.call_init();
+	simulation.run().unwrap();
+	simulation.call_terminate();
+
+	println!("\ntrace:");
+	let trace = simulation.trace().unwrap();
+	for record in trace.records() {
+		println!("{:>6}ms {:<10} {:?}", record.time.as_millis(), trace.component_name(record.component), record.kind);
+	}
+}
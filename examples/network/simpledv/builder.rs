@@ -136,9 +136,9 @@ impl NetworkBuilder {
     }
 
     pub(crate) fn build_sim(self, sim: &mut Simulation<DelayChannel>) {
-        let topo = self.topo_builder.build_topo();
+        let mut topo = self.topo_builder.build_topo();
         let mut nc = NetworkComponentBuilder::new();
-        sim.build_from_topo(*topo, &mut nc);
+        sim.build_from_topo(&mut topo, &mut nc);
     }
 
     pub fn debug(&self) {
@@ -0,0 +1,32 @@
+use d2simrs::*;
+use d2simrs::algorithms::leader_election::build_ring;
+use d2simrs::algorithms::leader_election::hs::{HsBuilder, HsMessage, ProcessModel};
+use d2simrs::channel::{Channel, ChannelBuilder};
+use d2simrs::synch::channel::BasicChannelBuilder;
+
+const NUM_NODES: usize = 16;
+
+fn elect<ChannelT, B>(model: ProcessModel, channel_builder: &mut B)
+	where ChannelT: Channel, B: ChannelBuilder<C = ChannelT>
+{
+	let mut simulation = Simulation::<ChannelT>::default();
+	let mut process_builder = HsBuilder::new(1000);
+	process_builder.model(model);
+
+	build_ring(&mut simulation, &mut process_builder, channel_builder, NUM_NODES);
+	simulation.register_message::<HsMessage>("hs");
+
+	simulation.call_init();
+	simulation.run().unwrap();
+	simulation.call_terminate();
+
+	println!("\n{:?}: {:?}", model, process_builder.election().borrow());
+	print!("{}", simulation.complexity());
+}
+
+fn main() {
+	println!("HS Algorithm for Leader Election in bidirectional Rings");
+
+	elect(ProcessModel::Synch, &mut BasicChannelBuilder {});
+	elect(ProcessModel::Asynch, DelayChannelBuilder::new().delay_millis(100));
+}
@@ -0,0 +1,143 @@
+use std::fmt;
+
+use d2simrs::*;
+use d2simrs::synch::batch::BatchProcess;
+use d2simrs::synch::process::ProcessId;
+use d2simrs::synch::round::{Round, RoundDriver};
+use d2simrs::util::uid::{UIdGenRandom, UniqueId};
+
+// process builder -------------------
+pub struct ProcessBuilder {
+    uid_gen: UIdGenRandom,
+}
+
+impl ProcessBuilder {
+    pub fn new(max_uid: usize) -> Self {
+        ProcessBuilder { uid_gen: UIdGenRandom::new(max_uid) }
+    }
+}
+
+impl ComponentBuilder for ProcessBuilder {
+    fn build_component(&mut self, pid: ComponentId) -> Box<dyn Component> {
+        Box::new(RoundDriver::new(Process {
+            process_id: pid,
+            left: ChannelId::default(),
+            right: ChannelId::default(),
+            uid: self.uid_gen.generate_uid(),
+            send: None,
+            state: State::Unknown,
+            halted: false,
+        }))
+    }
+}
+
+// end process builder -------------------
+
+// process  -------------------
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum State {
+    Unknown, Leader, NonLeader(UniqueId),
+}
+
+#[derive(Debug)]
+pub struct Process {
+    process_id: ProcessId,
+    left: ChannelId,
+    right: ChannelId,
+    //--------
+    uid: UniqueId,
+    send: Option<Message>,
+    state: State,
+    halted: bool,
+}
+
+impl BatchProcess for Process {
+    type Msg = Message;
+
+    fn id(&self) -> ProcessId {
+        self.process_id
+    }
+
+    fn add_channel(&mut self, channel_id: ChannelId, label: ChannelLabel) {
+        match label {
+            ChannelLabel::Left => { self.left = channel_id }
+            ChannelLabel::Right => { self.right = channel_id }
+        }
+    }
+
+    fn init(&mut self) {
+        assert!(self.left.is_initialized());
+        assert!(self.right.is_initialized());
+        self.send = Some(Message::SendUId(self.uid));
+    }
+
+    fn round(&mut self, round: Round, inbox: &[(ChannelId, Message)]) -> Vec<(ChannelId, Message)> {
+        // the whole inbox of the round is known, at most one message arrives from the right
+        assert!(inbox.len() <= 1);
+
+        if let Some((channel, msg)) = inbox.first() {
+            assert_eq!(*channel, self.right);
+            println!("[round {}] process {} received msg {:?}", round, self, msg);
+
+            self.send = match *msg {
+                Message::SendUId(uid) if uid > self.uid => Some(*msg),
+                Message::SendUId(uid) if uid < self.uid => None,
+                Message::SendUId(_) => {
+                    println!("\ti am the leader");
+                    self.state = State::Leader;
+                    Some(Message::Terminate(self.uid))
+                }
+                Message::Terminate(leader) if leader == self.uid => {
+                    // terminate message went around the ring
+                    self.halted = true;
+                    None
+                }
+                Message::Terminate(leader) => {
+                    // forward terminate message and halt
+                    self.state = State::NonLeader(leader);
+                    self.halted = true;
+                    Some(*msg)
+                }
+            };
+        }
+
+        match self.send.take() {
+            Some(msg) => vec![(self.left, msg)],
+            None => vec![],
+        }
+    }
+
+    fn is_halted(&self) -> bool {
+        self.halted
+    }
+
+    fn terminate(&mut self) {
+        println!("terminating process {}, state {:?}", self, self.state);
+        assert_ne!(self.state, State::Unknown);
+    }
+}
+
+impl Process {
+    pub fn state(&self) -> State {
+        self.state
+    }
+}
+
+impl fmt::Display for Process {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Process {{ process_id {:?}, uid {:?} }}", self.process_id, self.uid)
+    }
+}
+
+// end process  -------------------
+
+// message -------------------
+
+#[derive(Debug, Copy, Clone)]
+pub enum Message {
+    SendUId(UniqueId),
+    Terminate(UniqueId),
+}
+
+// end message -------------------
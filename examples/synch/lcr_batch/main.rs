@@ -0,0 +1,46 @@
+use d2simrs::*;
+use d2simrs::synch::round::{RoundChannel, RoundChannelBuilder};
+
+use crate::lcr::{Message, Process, ProcessBuilder, State};
+
+mod lcr;
+
+fn main() {
+	println!("LCR Algorithm for Leader Election in Rings (batch round API)");
+
+	let mut simulation = Simulation::<RoundChannel>::default();
+	let mut process_builder = ProcessBuilder::new(100);
+
+	const NUM_NODES: usize = 10;
+	let mut nodes: Vec<ComponentId> = Vec::with_capacity(NUM_NODES);
+
+	// create nodes
+	for _ in 0..NUM_NODES {
+		let node = simulation.add_component(&mut process_builder);
+		nodes.push(node);
+	}
+
+	// connect nodes by channels in a ring
+	let mut channel_builder = RoundChannelBuilder::default();
+
+	for idx0 in 0..NUM_NODES {
+		let idx1 = (idx0 + 1) % NUM_NODES;
+		simulation.add_channel(&mut channel_builder, nodes[idx0], nodes[idx1]);
+	}
+
+	simulation.register_message::<Message>("uid");
+
+	simulation.call_init();
+
+	simulation.run().unwrap();
+
+	simulation.call_terminate();
+
+	let leaders: Vec<_> = simulation.components_of::<Process>()
+		.filter(|(_, process)| process.state() == State::Leader)
+		.collect();
+	assert_eq!(leaders.len(), 1);
+	println!("\nleader: {}", leaders[0].1);
+
+	print!("\n{}", simulation.complexity());
+}
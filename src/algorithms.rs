@@ -0,0 +1,2 @@
+pub mod leader_election;
+pub mod mst;
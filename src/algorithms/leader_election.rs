@@ -0,0 +1,16 @@
+pub use self::ring::build_ring;
+
+pub mod hs;
+pub mod ring;
+
+use crate::util::uid::UniqueId;
+
+/// outcome of a leader election shared by all processes of a run
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Election {
+    pub leader: Option<UniqueId>,
+    /// processes that know the leader
+    pub decided: usize,
+    /// messages sent by the algorithm
+    pub messages: usize,
+}
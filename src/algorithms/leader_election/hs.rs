@@ -0,0 +1,360 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+use crate::algorithms::leader_election::Election;
+use crate::asynch::process::{AsynchComponent, AsynchProcess};
+use crate::component::{ChannelLabel, Component, ComponentBase, ComponentBuilder};
+use crate::keys::{ChannelId, ComponentId};
+use crate::simvars::sim_sched;
+use crate::synch::process::{ProcessBase, SynchProcess};
+use crate::util::uid::{UIdGenRandom, UniqueId};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum HsMessage {
+    /// probe travelling away from its sender
+    Out { uid: UniqueId, phase: u32, hops: usize },
+    /// reply travelling back to the sender of the probe
+    In { uid: UniqueId, phase: u32 },
+    /// announcement of the elected leader
+    Leader(UniqueId),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum State {
+    Unknown, Leader, NonLeader(UniqueId),
+}
+
+/// Hirschberg-Sinclair leader election in a bidirectional ring
+///
+/// in phase `l` every process still competing sends its uid `2^l` hops in both directions
+/// and waits for both replies. probes are discarded by processes with a larger uid, the
+/// process that receives its own probe is the leader and announces itself around the ring.
+/// the algorithm is message-driven, so the same state machine runs in a synchronous ring
+/// (`SynchProcess` with `BasicChannel`) and in an asynchronous ring (`AsynchProcess`
+/// with fifo channels, e.g. `DelayChannel`). use `build_ring` to connect the processes
+///
+/// `Hs` is the state machine of a single process, independent of the process model
+#[derive(Debug)]
+pub struct Hs {
+    uid: UniqueId,
+    left: ChannelId,
+    right: ChannelId,
+    phase: u32,
+    replies: u8,
+    state: State,
+    terminated: bool,
+    election: Rc<RefCell<Election>>,
+}
+
+impl Hs {
+    pub fn new(uid: UniqueId, election: Rc<RefCell<Election>>) -> Self {
+        Hs {
+            uid,
+            left: ChannelId::default(),
+            right: ChannelId::default(),
+            phase: 0,
+            replies: 0,
+            state: State::Unknown,
+            terminated: false,
+            election,
+        }
+    }
+
+    pub fn uid(&self) -> UniqueId {
+        self.uid
+    }
+
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    pub fn phase(&self) -> u32 {
+        self.phase
+    }
+
+    pub fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+
+    pub fn add_channel(&mut self, channel_id: ChannelId, label: ChannelLabel) {
+        match label {
+            ChannelLabel::Left => { self.left = channel_id }
+            ChannelLabel::Right => { self.right = channel_id }
+        }
+    }
+
+    /// messages of phase zero
+    pub fn start(&mut self) -> Vec<(ChannelId, HsMessage)> {
+        assert!(self.left.is_initialized() && self.right.is_initialized(), "process {} is not in a ring", self.uid);
+        self.probe()
+    }
+
+    /// handles a message, returns messages to send
+    pub fn receive(&mut self, channel: ChannelId, msg: HsMessage) -> Vec<(ChannelId, HsMessage)> {
+        if self.terminated {
+            return vec![];
+        }
+
+        let other = if channel == self.left { self.right } else { self.left };
+
+        match msg {
+            HsMessage::Out { uid, phase, hops } => {
+                match uid.partial_cmp(&self.uid).unwrap() {
+                    Ordering::Greater if hops > 1 => vec![(other, HsMessage::Out { uid, phase, hops: hops - 1 })],
+                    Ordering::Greater => vec![(channel, HsMessage::In { uid, phase })],
+                    Ordering::Less => vec![],
+                    Ordering::Equal if self.state == State::Unknown => {
+                        self.state = State::Leader;
+                        let mut election = self.election.borrow_mut();
+                        election.leader = Some(self.uid);
+                        election.decided += 1;
+                        vec![(self.left, HsMessage::Leader(self.uid))]
+                    }
+                    // own probe arriving from the other direction
+                    Ordering::Equal => vec![],
+                }
+            }
+            HsMessage::In { uid, .. } if uid != self.uid => vec![(other, msg)],
+            HsMessage::In { phase, .. } => {
+                if phase != self.phase || self.state != State::Unknown {
+                    return vec![];
+                }
+
+                self.replies += 1;
+                if self.replies < 2 {
+                    return vec![];
+                }
+
+                self.phase += 1;
+                self.replies = 0;
+                self.probe()
+            }
+            HsMessage::Leader(leader) => {
+                self.terminated = true;
+                if leader == self.uid {
+                    return vec![];
+                }
+
+                self.state = State::NonLeader(leader);
+                self.election.borrow_mut().decided += 1;
+                vec![(other, msg)]
+            }
+        }
+    }
+
+    fn probe(&self) -> Vec<(ChannelId, HsMessage)> {
+        let msg = HsMessage::Out { uid: self.uid, phase: self.phase, hops: 1 << self.phase };
+        vec![(self.left, msg), (self.right, msg)]
+    }
+
+    fn send(&self, sender: ComponentId, out: Vec<(ChannelId, HsMessage)>) {
+        self.election.borrow_mut().messages += out.len();
+        for (channel, msg) in out {
+            sim_sched().send_msg(sender, channel, Box::new(msg));
+        }
+    }
+}
+
+// synchronous process -------------------
+
+#[derive(Debug)]
+pub struct HsSynchProcess {
+    base: ProcessBase,
+    hs: Hs,
+}
+
+impl HsSynchProcess {
+    pub fn hs(&self) -> &Hs {
+        &self.hs
+    }
+}
+
+impl SynchProcess for HsSynchProcess {
+    fn get_sim_base(&self) -> &ProcessBase {
+        &self.base
+    }
+
+    fn get_sim_base_mut(&mut self) -> &mut ProcessBase {
+        &mut self.base
+    }
+
+    fn add_channel(&mut self, channel_id: ChannelId, label: ChannelLabel) {
+        self.hs.add_channel(channel_id, label);
+        self.base.add_channel(channel_id, label);
+    }
+
+    fn round_zero(&mut self) {
+        let out = self.hs.start();
+        self.hs.send(SynchProcess::id(self), out);
+    }
+
+    fn start_new_round(&mut self) {}
+
+    fn receive_msg(&mut self, incoming_channel: ChannelId, msg: Box<dyn Any>) {
+        let msg = msg.downcast::<HsMessage>().unwrap();
+        let out = self.hs.receive(incoming_channel, *msg);
+        self.hs.send(SynchProcess::id(self), out);
+    }
+
+    fn terminate(&mut self) {}
+
+    fn is_terminated(&self) -> bool {
+        self.hs.is_terminated()
+    }
+}
+
+// asynchronous process -------------------
+
+#[derive(Debug)]
+pub struct HsAsynchProcess {
+    base: ComponentBase,
+    hs: Hs,
+}
+
+impl HsAsynchProcess {
+    pub fn hs(&self) -> &Hs {
+        &self.hs
+    }
+}
+
+impl AsynchProcess for HsAsynchProcess {
+    type Msg = HsMessage;
+
+    fn get_sim_base(&self) -> &ComponentBase {
+        &self.base
+    }
+
+    fn get_sim_base_mut(&mut self) -> &mut ComponentBase {
+        &mut self.base
+    }
+
+    fn add_channel(&mut self, channel_id: ChannelId, label: ChannelLabel) {
+        self.hs.add_channel(channel_id, label);
+        self.base.add_channel(channel_id, label);
+    }
+
+    fn on_start(&mut self) {
+        let out = self.hs.start();
+        self.hs.send(self.id(), out);
+    }
+
+    fn on_message(&mut self, incoming_channel: ChannelId, msg: HsMessage) {
+        let out = self.hs.receive(incoming_channel, msg);
+        self.hs.send(self.id(), out);
+    }
+
+    fn terminate(&mut self) {}
+
+    fn is_terminated(&self) -> bool {
+        self.hs.is_terminated()
+    }
+}
+
+// builder -------------------
+
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum ProcessModel {
+    /// `HsSynchProcess`, connect with `synch::channel::BasicChannel`
+    #[default]
+    Synch,
+    /// `HsAsynchProcess` in an `AsynchComponent`, connect with fifo channels
+    Asynch,
+}
+
+/// builds HS processes with random or given uids
+pub struct HsBuilder {
+    uid_gen: UIdGenRandom,
+    uids: Vec<UniqueId>,
+    model: ProcessModel,
+    election: Rc<RefCell<Election>>,
+}
+
+impl HsBuilder {
+    pub fn new(max_uid: usize) -> Self {
+        HsBuilder {
+            uid_gen: UIdGenRandom::new(max_uid),
+            uids: Vec::new(),
+            model: ProcessModel::default(),
+            election: Rc::default(),
+        }
+    }
+
+    /// i-th process gets the i-th uid
+    pub fn with_uids(uids: Vec<UniqueId>) -> Self {
+        let mut builder = Self::new(usize::MAX);
+        builder.uids = uids;
+        builder.uids.reverse();
+        builder
+    }
+
+    pub fn model(&mut self, model: ProcessModel) -> &mut Self {
+        self.model = model;
+        self
+    }
+
+    /// outcome of the election, updated while the simulation runs
+    pub fn election(&self) -> Rc<RefCell<Election>> {
+        self.election.clone()
+    }
+}
+
+impl ComponentBuilder for HsBuilder {
+    fn build_component(&mut self, id: ComponentId) -> Box<dyn Component> {
+        let uid = self.uids.pop().unwrap_or_else(|| self.uid_gen.generate_uid());
+        let hs = Hs::new(uid, self.election.clone());
+
+        match self.model {
+            ProcessModel::Synch => Box::new(HsSynchProcess { base: ProcessBase::new(id), hs }),
+            ProcessModel::Asynch => Box::new(AsynchComponent::new(HsAsynchProcess { base: ComponentBase::new(id), hs })),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algorithms::leader_election::build_ring;
+    use crate::channel::{Channel, ChannelBuilder};
+    use crate::channels::delay_channel::DelayChannelBuilder;
+    use crate::sim::Simulation;
+    use crate::synch::channel::BasicChannelBuilder;
+
+    const UIDS: [usize; 8] = [3, 17, 5, 12, 40, 1, 9, 22];
+
+    fn elect<ChannelT, B>(model: ProcessModel, channel_builder: &mut B) -> Election
+        where ChannelT: Channel, B: ChannelBuilder<C = ChannelT>
+    {
+        let mut builder = HsBuilder::with_uids(UIDS.iter().map(|uid| UniqueId(*uid)).collect());
+        builder.model(model);
+
+        let mut simulation = Simulation::<ChannelT>::default();
+        build_ring(&mut simulation, &mut builder, channel_builder, UIDS.len());
+        simulation.call_init();
+        simulation.run().unwrap();
+
+        assert!(simulation.termination_report().all_terminated);
+        let states: Vec<State> = simulation.components_of::<HsSynchProcess>().map(|(_, p)| p.hs().state())
+            .chain(simulation.components_of::<HsAsynchProcess>().map(|(_, p)| p.hs().state()))
+            .collect();
+        assert_eq!(states.len(), UIDS.len());
+        assert_eq!(states.iter().filter(|s| **s == State::Leader).count(), 1);
+
+        builder.election().borrow().clone()
+    }
+
+    #[test]
+    fn test_synch_and_asynch_elect_max_uid() {
+        let n = UIDS.len();
+        // 8n(1 + log n)
+        let bound = 8 * n * (1 + n.ilog2() as usize);
+
+        for election in [elect(ProcessModel::Synch, &mut BasicChannelBuilder {}),
+                         elect(ProcessModel::Asynch, DelayChannelBuilder::new().delay_millis(10))] {
+            assert_eq!(election.leader, Some(UniqueId(40)));
+            assert_eq!(election.decided, n);
+            assert!(election.messages <= bound, "{} messages", election.messages);
+        }
+    }
+}
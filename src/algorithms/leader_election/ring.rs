@@ -0,0 +1,26 @@
+use crate::channel::{Channel, ChannelBuilder};
+use crate::component::ComponentBuilder;
+use crate::keys::ComponentId;
+use crate::sim::Simulation;
+
+/// adds `n` components connected in a bidirectional ring
+///
+/// component `i` is the left end of the channel to component `i + 1`, so the channel it
+/// gets with `ChannelLabel::Left` leads to its successor and the one with `ChannelLabel::Right`
+/// to its predecessor. messages sent to the left travel clockwise around the ring
+pub fn build_ring<ChannelT, B>(simulation: &mut Simulation<ChannelT>,
+                               component_builder: &mut dyn ComponentBuilder,
+                               channel_builder: &mut B,
+                               n: usize) -> Vec<ComponentId>
+    where ChannelT: Channel, B: ChannelBuilder<C = ChannelT>
+{
+    let nodes: Vec<ComponentId> = (0..n)
+        .map(|_| simulation.add_component(component_builder))
+        .collect();
+
+    for idx in 0..n {
+        simulation.add_channel(channel_builder, nodes[idx], nodes[(idx + 1) % n]);
+    }
+
+    nodes
+}
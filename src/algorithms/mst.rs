@@ -0,0 +1,65 @@
+pub mod ghs;
+
+use std::collections::HashSet;
+
+use petgraph::algo::min_spanning_tree;
+use petgraph::data::Element;
+use petgraph::unionfind::UnionFind;
+
+use crate::keys::ChannelId;
+use crate::topo::topo::Topology;
+
+/// spanning tree computed by a distributed algorithm, shared by all processes of a run
+#[derive(Debug, Default, Clone)]
+pub struct SpanningTree {
+    /// channels in the tree
+    pub branches: HashSet<ChannelId>,
+    /// processes that know the tree is complete
+    pub halted: usize,
+    /// messages sent by the algorithm
+    pub messages: usize,
+}
+
+/// checks that `tree` is a minimum spanning tree of the topology the simulation was built from
+///
+/// the tree must span all nodes without cycles and weigh as much as the tree found by
+/// petgraph's `min_spanning_tree` (trees differ only if edges have equal distances).
+/// returns the weight of the tree
+pub fn verify_mst<V, E>(topo: &Topology<V, E>, tree: &HashSet<ChannelId>) -> Result<f64, String> {
+    let g = &topo.g;
+
+    if tree.len() + 1 != g.node_count() {
+        return Err(format!("tree has {} edges, expected {}", tree.len(), g.node_count() - 1));
+    }
+
+    let mut components = UnionFind::new(g.node_count());
+    let mut num_edges = 0;
+    let mut weight = 0.0;
+
+    for idx in g.edge_indices().filter(|idx| g[*idx].channle_id.is_some_and(|ch| tree.contains(&ch))) {
+        let (a, b) = g.edge_endpoints(idx).unwrap();
+        if !components.union(a.index(), b.index()) {
+            return Err(format!("tree has a cycle through channel {:?}", g[idx].channle_id.unwrap()));
+        }
+        num_edges += 1;
+        weight += g[idx].distance;
+    }
+
+    if num_edges != tree.len() {
+        return Err(format!("{} channel(s) of the tree are not edges of the topology", tree.len() - num_edges));
+    }
+
+    let distances = g.map(|_, _| (), |_, e| e.distance);
+    let expected: f64 = min_spanning_tree(&distances)
+        .filter_map(|element| match element {
+            Element::Edge { weight, .. } => Some(weight),
+            Element::Node { .. } => None,
+        })
+        .sum();
+
+    if (weight - expected).abs() > 1e-9 * expected.abs().max(1.0) {
+        return Err(format!("tree weighs {}, minimum spanning tree weighs {}", weight, expected));
+    }
+
+    Ok(weight)
+}
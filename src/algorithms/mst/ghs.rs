@@ -0,0 +1,459 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+use crate::algorithms::mst::SpanningTree;
+use crate::asynch::process::{AsynchComponent, AsynchProcess};
+use crate::channel::ChannelBuilder;
+use crate::component::{Component, ComponentBase, ComponentBuilder};
+use crate::keys::{ChannelId, ComponentId};
+use crate::topo::FromGraphBuilder;
+use crate::topo::topo::Topology;
+
+/// edge weight, distinct for all edges
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct Weight(f64, usize);
+
+const INFINITY: Weight = Weight(f64::INFINITY, usize::MAX);
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum GhsMessage {
+    Connect { level: u32 },
+    Initiate { level: u32, fragment: Weight, state: NodeState },
+    Test { level: u32, fragment: Weight },
+    Accept,
+    Reject,
+    Report(Weight),
+    ChangeRoot,
+    /// the tree is complete, sent by the core over all branches
+    Halt,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum NodeState {
+    Sleeping, Find, Found,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum EdgeState {
+    Basic, Branch, Rejected,
+}
+
+#[derive(Debug)]
+struct Edge {
+    channel: ChannelId,
+    weight: Weight,
+    state: EdgeState,
+}
+
+/// Gallager-Humblet-Spira minimum spanning tree
+///
+/// fragments, starting from single processes, find their minimum-weight outgoing edge and
+/// merge or absorb each other along it until a single fragment spans the network. edge
+/// weights are the distances of the topology edges, equal distances are ordered by channel
+/// id so that all weights are distinct. the algorithm needs fifo channels (e.g. `DelayChannel`).
+///
+/// build the simulation with `Simulation::build_from_topo` and a `GhsBuilder`, then call
+/// `GhsBuilder::set_weights` with the same topology before `call_init`
+#[derive(Debug)]
+pub struct GhsProcess {
+    base: ComponentBase,
+    weights: Rc<RefCell<HashMap<ChannelId, f64>>>,
+    tree: Rc<RefCell<SpanningTree>>,
+    // sorted by weight
+    edges: Vec<Edge>,
+    state: NodeState,
+    level: u32,
+    fragment: Weight,
+    in_branch: Option<usize>,
+    best_edge: Option<usize>,
+    best_weight: Weight,
+    test_edge: Option<usize>,
+    find_count: usize,
+    halted: bool,
+    // messages that can not be processed in the current state
+    deferred: Vec<(usize, GhsMessage)>,
+}
+
+impl GhsProcess {
+    pub fn state(&self) -> NodeState {
+        self.state
+    }
+
+    pub fn level(&self) -> u32 {
+        self.level
+    }
+
+    /// channels of the process in the spanning tree
+    pub fn branches(&self) -> Vec<ChannelId> {
+        self.edges.iter()
+            .filter(|e| e.state == EdgeState::Branch)
+            .map(|e| e.channel)
+            .collect()
+    }
+
+    fn edge_idx(&self, channel: ChannelId) -> usize {
+        self.edges.iter().position(|e| e.channel == channel)
+            .unwrap_or_else(|| panic!("process {:?} has no channel {:?}", self.id(), channel))
+    }
+
+    fn send_on(&self, edge: usize, msg: GhsMessage) {
+        self.tree.borrow_mut().messages += 1;
+        self.send(self.edges[edge].channel, msg);
+    }
+
+    fn wakeup(&mut self) {
+        // edges are sorted, the first one has the minimum weight
+        self.edges[0].state = EdgeState::Branch;
+        self.level = 0;
+        self.state = NodeState::Found;
+        self.find_count = 0;
+        self.send_on(0, GhsMessage::Connect { level: 0 });
+    }
+
+    /// returns false if the message has to be processed later
+    fn handle(&mut self, j: usize, msg: GhsMessage) -> bool {
+        match msg {
+            GhsMessage::Connect { level } => {
+                if self.state == NodeState::Sleeping {
+                    self.wakeup();
+                }
+
+                if level < self.level {
+                    self.edges[j].state = EdgeState::Branch;
+                    self.send_on(j, GhsMessage::Initiate { level: self.level, fragment: self.fragment, state: self.state });
+                    if self.state == NodeState::Find {
+                        self.find_count += 1;
+                    }
+                } else if self.edges[j].state == EdgeState::Basic {
+                    return false;
+                } else {
+                    let fragment = self.edges[j].weight;
+                    self.send_on(j, GhsMessage::Initiate { level: self.level + 1, fragment, state: NodeState::Find });
+                }
+            }
+            GhsMessage::Initiate { level, fragment, state } => {
+                self.level = level;
+                self.fragment = fragment;
+                self.state = state;
+                self.in_branch = Some(j);
+                self.best_edge = None;
+                self.best_weight = INFINITY;
+
+                for i in 0..self.edges.len() {
+                    if i != j && self.edges[i].state == EdgeState::Branch {
+                        self.send_on(i, msg);
+                        if state == NodeState::Find {
+                            self.find_count += 1;
+                        }
+                    }
+                }
+
+                if state == NodeState::Find {
+                    self.test();
+                }
+            }
+            GhsMessage::Test { level, fragment } => {
+                if self.state == NodeState::Sleeping {
+                    self.wakeup();
+                }
+
+                if level > self.level {
+                    return false;
+                }
+
+                if fragment != self.fragment {
+                    self.send_on(j, GhsMessage::Accept);
+                } else {
+                    if self.edges[j].state == EdgeState::Basic {
+                        self.edges[j].state = EdgeState::Rejected;
+                    }
+
+                    if self.test_edge != Some(j) {
+                        self.send_on(j, GhsMessage::Reject);
+                    } else {
+                        self.test();
+                    }
+                }
+            }
+            GhsMessage::Accept => {
+                self.test_edge = None;
+                if self.edges[j].weight < self.best_weight {
+                    self.best_edge = Some(j);
+                    self.best_weight = self.edges[j].weight;
+                }
+                self.report();
+            }
+            GhsMessage::Reject => {
+                if self.edges[j].state == EdgeState::Basic {
+                    self.edges[j].state = EdgeState::Rejected;
+                }
+                self.test();
+            }
+            GhsMessage::Report(weight) => {
+                if Some(j) != self.in_branch {
+                    self.find_count -= 1;
+                    if weight < self.best_weight {
+                        self.best_weight = weight;
+                        self.best_edge = Some(j);
+                    }
+                    self.report();
+                } else if self.state == NodeState::Find {
+                    return false;
+                } else if weight > self.best_weight {
+                    self.change_root();
+                } else if weight == INFINITY && self.best_weight == INFINITY {
+                    // both core processes get here, each one halts its half of the tree
+                    self.halt(Some(j));
+                }
+            }
+            GhsMessage::ChangeRoot => {
+                self.change_root();
+            }
+            GhsMessage::Halt => {
+                self.halt(Some(j));
+            }
+        }
+
+        true
+    }
+
+    fn test(&mut self) {
+        self.test_edge = self.edges.iter().position(|e| e.state == EdgeState::Basic);
+
+        match self.test_edge {
+            Some(edge) => self.send_on(edge, GhsMessage::Test { level: self.level, fragment: self.fragment }),
+            None => self.report(),
+        }
+    }
+
+    fn report(&mut self) {
+        if self.find_count == 0 && self.test_edge.is_none() {
+            self.state = NodeState::Found;
+            self.send_on(self.in_branch.unwrap(), GhsMessage::Report(self.best_weight));
+        }
+    }
+
+    fn change_root(&mut self) {
+        let best_edge = self.best_edge.unwrap();
+
+        if self.edges[best_edge].state == EdgeState::Branch {
+            self.send_on(best_edge, GhsMessage::ChangeRoot);
+        } else {
+            self.send_on(best_edge, GhsMessage::Connect { level: self.level });
+            self.edges[best_edge].state = EdgeState::Branch;
+        }
+    }
+
+    fn halt(&mut self, from: Option<usize>) {
+        self.halted = true;
+
+        for i in 0..self.edges.len() {
+            if Some(i) != from && self.edges[i].state == EdgeState::Branch {
+                self.send_on(i, GhsMessage::Halt);
+            }
+        }
+
+        let mut tree = self.tree.borrow_mut();
+        tree.halted += 1;
+        tree.branches.extend(self.branches());
+    }
+
+    /// retries deferred messages until none of them can be processed
+    fn process_deferred(&mut self) {
+        loop {
+            let deferred = std::mem::take(&mut self.deferred);
+            let num_deferred = deferred.len();
+
+            for (j, msg) in deferred {
+                if !self.handle(j, msg) {
+                    self.deferred.push((j, msg));
+                }
+            }
+
+            if self.deferred.len() == num_deferred {
+                break;
+            }
+        }
+    }
+}
+
+impl AsynchProcess for GhsProcess {
+    type Msg = GhsMessage;
+
+    fn get_sim_base(&self) -> &ComponentBase {
+        &self.base
+    }
+
+    fn get_sim_base_mut(&mut self) -> &mut ComponentBase {
+        &mut self.base
+    }
+
+    fn init(&mut self) {
+        let weights = self.weights.borrow();
+        self.edges = self.base.channels.iter()
+            .map(|ch| {
+                let distance = weights.get(ch)
+                    .unwrap_or_else(|| panic!("no weight for channel {:?}, call GhsBuilder::set_weights", ch));
+                Edge { channel: *ch, weight: Weight(*distance, ch.as_idx()), state: EdgeState::Basic }
+            })
+            .collect();
+        self.edges.sort_by(|a, b| a.weight.partial_cmp(&b.weight).unwrap());
+    }
+
+    fn on_start(&mut self) {
+        if self.edges.is_empty() {
+            self.halt(None);
+        } else if self.state == NodeState::Sleeping {
+            self.wakeup();
+        }
+    }
+
+    fn on_message(&mut self, incoming_channel: ChannelId, msg: GhsMessage) {
+        if self.halted {
+            return;
+        }
+
+        let j = self.edge_idx(incoming_channel);
+        if self.handle(j, msg) {
+            self.process_deferred();
+        } else {
+            self.deferred.push((j, msg));
+        }
+    }
+
+    fn terminate(&mut self) {}
+
+    fn is_terminated(&self) -> bool {
+        self.halted
+    }
+}
+
+// builder -------------------
+
+/// builds GHS processes and the channels between them from a topology
+///
+/// `V` and `E` are the node and edge data of the topology
+pub struct GhsBuilder<CB: ChannelBuilder, V = (), E = ()> {
+    channel_builder: CB,
+    weights: Rc<RefCell<HashMap<ChannelId, f64>>>,
+    tree: Rc<RefCell<SpanningTree>>,
+    _marker: PhantomData<(V, E)>,
+}
+
+impl<CB: ChannelBuilder, V, E> GhsBuilder<CB, V, E> {
+    pub fn new(channel_builder: CB) -> Self {
+        GhsBuilder {
+            channel_builder,
+            weights: Rc::default(),
+            tree: Rc::default(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// uses distances of the topology edges as weights of their channels,
+    /// the topology must have been built into the simulation
+    pub fn set_weights(&mut self, topo: &Topology<V, E>) {
+        let g = &topo.g;
+        let mut weights = self.weights.borrow_mut();
+
+        for idx in g.edge_indices() {
+            let channel = g[idx].channle_id.expect("topology was not built into the simulation");
+            weights.insert(channel, g[idx].distance);
+        }
+    }
+
+    /// spanning tree, complete once all processes halted
+    pub fn tree(&self) -> Rc<RefCell<SpanningTree>> {
+        self.tree.clone()
+    }
+}
+
+impl<CB: ChannelBuilder, V, E> ComponentBuilder for GhsBuilder<CB, V, E> {
+    fn build_component(&mut self, id: ComponentId) -> Box<dyn Component> {
+        Box::new(AsynchComponent::new(GhsProcess {
+            base: ComponentBase::new(id),
+            weights: self.weights.clone(),
+            tree: self.tree.clone(),
+            edges: Vec::new(),
+            state: NodeState::Sleeping,
+            level: 0,
+            fragment: INFINITY,
+            in_branch: None,
+            best_edge: None,
+            best_weight: INFINITY,
+            test_edge: None,
+            find_count: 0,
+            halted: false,
+            deferred: Vec::new(),
+        }))
+    }
+}
+
+impl<CB: ChannelBuilder, V, E> ChannelBuilder for GhsBuilder<CB, V, E> {
+    type C = CB::C;
+
+    fn build_channel(&self, c: ChannelId, p0: ComponentId, p1: ComponentId) -> Self::C {
+        self.channel_builder.build_channel(c, p0, p1)
+    }
+}
+
+impl<CB: ChannelBuilder, V, E> FromGraphBuilder for GhsBuilder<CB, V, E> {
+    type Node = V;
+    type Edge = E;
+
+    fn node_cfg(&mut self, _node: &V) -> &mut Self {
+        self
+    }
+
+    fn edge_cfg(&mut self, _edge: &E) -> &mut Self {
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algorithms::mst::verify_mst;
+    use crate::channels::delay_channel::{DelayChannel, DelayChannelBuilder};
+    use crate::sim::Simulation;
+    use crate::topo::anchoredrandomgraph::AnchoredRandomGraphGen;
+    use crate::topo::topobuilder::TopologyBuilder;
+    use crate::topo::topogen::TopologyGenerator;
+
+    fn run_ghs(mut topo: Topology) -> SpanningTree {
+        let mut builder = GhsBuilder::new(DelayChannelBuilder::with_delay(std::time::Duration::from_millis(10)));
+        let mut simulation = Simulation::<DelayChannel>::default();
+        simulation.build_from_topo(&mut topo, &mut builder);
+        builder.set_weights(&topo);
+
+        simulation.call_init();
+        simulation.run().unwrap();
+        assert!(simulation.termination_report().all_terminated);
+
+        let tree = builder.tree().borrow().clone();
+        assert_eq!(tree.halted, topo.num_nodes());
+        verify_mst(&topo, &tree.branches).unwrap();
+        tree
+    }
+
+    #[test]
+    fn test_ghs_finds_mst() {
+        // square with a diagonal, all sides have the same distance
+        let mut topo_builder = TopologyBuilder::new();
+        for _ in 0..4 {
+            topo_builder.add_node(());
+        }
+        for (from, to, distance) in [(0, 1, 1.0), (1, 2, 1.0), (2, 3, 1.0), (3, 0, 1.0), (0, 2, 0.5)] {
+            topo_builder.add_edge_with_distance(from, to, distance, ());
+        }
+        let tree = run_ghs(*topo_builder.build_topo());
+        assert_eq!(tree.branches.len(), 3);
+
+        for seed in 0..5 {
+            let gen = AnchoredRandomGraphGen::new(12, 0.3);
+            run_ghs(TopologyGenerator::new(12, gen).seed(seed).try_build_connected_network());
+        }
+    }
+}
@@ -0,0 +1 @@
+pub mod process;
\ No newline at end of file
@@ -0,0 +1,400 @@
+use std::any::{Any, TypeId};
+
+use crate::component::{ChannelLabel, Component, ComponentBase};
+use crate::gate::Gates;
+use crate::keys::{ChannelId, ComponentId};
+use crate::names::short_type_name;
+use crate::simtime::{NO_DELTA, SimTime, SimTimeDelta};
+use crate::simvars::{sim_local_clock, sim_sched};
+use crate::timer::{TimerHandler, TimerId, Timers};
+
+/// message-driven process of an asynchronous network
+///
+/// the process is started at time zero with `on_start` and afterwards only reacts
+/// to incoming messages of type `Msg`. wrap the process in an `AsynchComponent`
+/// to add it to the simulation.
+///
+/// there is no blanket `impl<P: AsynchProcess> Component for P`, it would overlap with the
+/// blanket impl for `SynchProcess` and coherence rejects two blanket impls of `Component`.
+/// the wrapper also holds the start event and timer dispatch without touching the process
+pub trait AsynchProcess {
+    type Msg: 'static;
+
+    fn get_sim_base(&self) -> &ComponentBase;
+
+    fn get_sim_base_mut(&mut self) -> &mut ComponentBase;
+
+    fn id(&self) -> ComponentId {
+        self.get_sim_base().component_id
+    }
+
+    /// time of the local clock, see `Simulation::enable_clock_drift`
+    fn local_time(&self) -> SimTime {
+        sim_local_clock(self.id()).now()
+    }
+
+    fn channels(&self) -> &[ChannelId] {
+        &self.get_sim_base().channels
+    }
+
+    fn add_channel(&mut self, channel_id: ChannelId, label: ChannelLabel) {
+        self.get_sim_base_mut().add_channel(channel_id, label);
+    }
+
+    /// declare gates in the builder with `ComponentBase::gates`
+    fn gates(&self) -> &Gates {
+        &self.get_sim_base().gates
+    }
+
+    //-----------------------------------------------------------------------
+
+    fn init(&mut self) {}
+
+    fn on_start(&mut self);
+
+    fn on_message(&mut self, incoming_channel: ChannelId, msg: Self::Msg);
+
+    /// processes events other than the start event, e.g. timers the process scheduled itself
+    fn on_event(&mut self, sender: ComponentId, _event: Box<dyn Any>) {
+        panic!("process {:?} received unexpected event from {:?}", self.id(), sender);
+    }
+
+    /// direct message that is not of type `Msg`, e.g. from an oracle, dropped by default
+    fn on_direct_message(&mut self, _msg: Box<dyn Any>) {
+        crate::sim_log!(self.id(), "dropped direct message of unexpected type");
+    }
+
+    fn terminate(&mut self);
+
+    fn is_terminated(&self) -> bool {
+        false
+    }
+
+    /// local state recorded by a global snapshot
+    fn snapshot_state(&self) -> Option<Box<dyn Any>> {
+        None
+    }
+
+    //-----------------------------------------------------------------------
+
+    fn send(&self, channel: ChannelId, msg: Self::Msg) {
+        sim_sched().send_msg(self.id(), channel, Box::new(msg));
+    }
+
+    /// delivers the message to `receiver` after `delay` without a channel, the receiver gets
+    /// it in `on_message` on `DIRECT_CHANNEL` or in `on_direct_message` if its type differs.
+    /// direct messages are not counted in the complexity report and not recorded by snapshots,
+    /// sending one while a snapshot is collected fails the run
+    fn send_direct<M: 'static>(&self, receiver: ComponentId, delay: SimTimeDelta, msg: M) {
+        sim_sched().send_direct(self.id(), receiver, delay, Box::new(msg));
+    }
+
+    fn timers(&self) -> &Timers {
+        &self.get_sim_base().timers
+    }
+
+    /// starts a timer that expires after `delay` in `TimerHandler::<T>::on_timer`
+    fn start_timer<T: 'static>(&mut self, delay: SimTimeDelta, timer: T) -> TimerId
+        where Self: TimerHandler<T> + Sized + 'static
+    {
+        let id = self.id();
+        let timers = &mut self.get_sim_base_mut().timers;
+        timers.set_handler(TypeId::of::<T>(), dispatch::<Self, T> as Dispatch<Self>);
+        timers.start(id, delay, timer)
+    }
+
+    /// starts a timer that expires every `period` plus a random delay of up to `jitter`
+    /// (`NO_DELTA` for none) until it is cancelled
+    fn start_periodic_timer<T: Clone + 'static>(&mut self, period: SimTimeDelta, jitter: SimTimeDelta, timer: T) -> TimerId
+        where Self: TimerHandler<T> + Sized + 'static
+    {
+        let id = self.id();
+        let timers = &mut self.get_sim_base_mut().timers;
+        timers.set_handler(TypeId::of::<T>(), dispatch::<Self, T> as Dispatch<Self>);
+        timers.start_periodic(id, period, jitter, timer)
+    }
+
+    /// returns false if the timer already expired or was cancelled
+    fn cancel_timer(&mut self, id: TimerId) -> bool {
+        self.get_sim_base_mut().timers.cancel(id)
+    }
+
+    /// sends on the channel connected to the gate, e.g. `out` or `port[1]`
+    fn send_on(&self, gate: &str, msg: Self::Msg) {
+        let channel = self.gates().channel(gate)
+            .unwrap_or_else(|| panic!("gate {} of process {:?} is not connected", gate, self.id()));
+        self.send(channel, msg);
+    }
+
+    /// sends a copy of the message on every channel
+    fn broadcast(&self, msg: Self::Msg)
+        where Self::Msg: Clone
+    {
+        for channel in self.channels() {
+            self.send(*channel, msg.clone());
+        }
+    }
+
+    /// sends a copy of the message on every channel except `except`
+    fn broadcast_except(&self, except: ChannelId, msg: Self::Msg)
+        where Self::Msg: Clone
+    {
+        for channel in self.channels() {
+            if *channel != except {
+                self.send(*channel, msg.clone());
+            }
+        }
+    }
+}
+
+type Dispatch<P> = fn(&mut P, TimerId, Box<dyn Any>);
+
+fn dispatch<P: TimerHandler<T>, T: 'static>(process: &mut P, id: TimerId, timer: Box<dyn Any>) {
+    process.on_timer(id, *timer.downcast::<T>().unwrap());
+}
+
+#[derive(Debug)]
+struct StartEvent;
+
+/// component running an asynchronous process
+///
+/// can not be used with `Explorer`, the start and timeout events are private and neither
+/// `Clone` nor `Hash`. implement `Component` directly for processes that are explored
+pub struct AsynchComponent<P: AsynchProcess> {
+    process: P,
+}
+
+impl<P: AsynchProcess> AsynchComponent<P> {
+    pub fn new(process: P) -> Self {
+        AsynchComponent { process }
+    }
+
+    pub fn process(&self) -> &P {
+        &self.process
+    }
+
+    pub fn process_mut(&mut self) -> &mut P {
+        &mut self.process
+    }
+}
+
+impl<P: AsynchProcess + 'static> Component for AsynchComponent<P> {
+
+    fn sim_id(&self) -> ComponentId {
+        self.process.id()
+    }
+
+    fn type_name(&self) -> String {
+        short_type_name(std::any::type_name::<P>())
+    }
+
+    fn add_channel(&mut self, channel_id: ChannelId, label: ChannelLabel) {
+        AsynchProcess::add_channel(&mut self.process, channel_id, label);
+    }
+
+    fn init(&mut self) {
+        AsynchProcess::init(&mut self.process);
+        sim_sched().sched_self_event_with_data(NO_DELTA, self.sim_id(), Box::new(StartEvent));
+    }
+
+    fn process_event(&mut self, sender: ComponentId, event: Box<dyn Any>) {
+        if event.is::<StartEvent>() {
+            assert_eq!(self.sim_id(), sender);
+            self.process.on_start();
+        } else if Timers::is_timeout(event.as_ref()) {
+            let timers = &mut self.process.get_sim_base_mut().timers;
+            if let Some((id, timer)) = timers.expire(sender, event) {
+                let dispatch: Dispatch<P> = timers.handler(timer.as_ref()).expect("timer without handler");
+                dispatch(&mut self.process, id, timer);
+            }
+        } else {
+            self.process.on_event(sender, event);
+        }
+    }
+
+    fn receive_msg(&mut self, incoming_channel: ChannelId, msg: Box<dyn Any>) {
+        match msg.downcast::<P::Msg>() {
+            Ok(msg) => self.process.on_message(incoming_channel, *msg),
+            Err(msg) if incoming_channel.is_direct() => self.process.on_direct_message(msg),
+            Err(_) => panic!("unexpected message type on channel {:?}", incoming_channel),
+        }
+    }
+
+    fn terminate(&mut self) {
+        AsynchProcess::terminate(&mut self.process);
+    }
+
+    fn is_terminated(&self) -> bool {
+        AsynchProcess::is_terminated(&self.process)
+    }
+
+    fn snapshot_state(&self) -> Option<Box<dyn Any>> {
+        AsynchProcess::snapshot_state(&self.process)
+    }
+
+    fn inner(&self) -> Option<&dyn Any> {
+        Some(&self.process)
+    }
+
+    fn inner_mut(&mut self) -> Option<&mut dyn Any> {
+        Some(&mut self.process)
+    }
+
+    fn gates(&self) -> Option<&Gates> {
+        Some(AsynchProcess::gates(&self.process))
+    }
+
+    fn gates_mut(&mut self) -> Option<&mut Gates> {
+        Some(&mut self.process.get_sim_base_mut().gates)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::channels::delay_channel::{DelayChannel, DelayChannelBuilder};
+    use crate::component::ComponentBuilder;
+    use crate::keys::DIRECT_CHANNEL;
+    use crate::sim::Simulation;
+    use crate::simtime::SimTime;
+    use crate::simvars::sim_time;
+    use crate::trace::TraceEventKind;
+
+    #[derive(Debug)]
+    struct Tick;
+
+    type Log = Rc<RefCell<Vec<(u128, usize, String)>>>;
+
+    /// node 0 starts a ping pong of three messages and a timer
+    struct Ping {
+        base: ComponentBase,
+        log: Log,
+    }
+
+    impl Ping {
+        fn log(&self, what: String) {
+            self.log.borrow_mut().push((sim_time().as_millis(), self.id().as_idx(), what));
+        }
+    }
+
+    impl AsynchProcess for Ping {
+        type Msg = u32;
+
+        fn get_sim_base(&self) -> &ComponentBase { &self.base }
+
+        fn get_sim_base_mut(&mut self) -> &mut ComponentBase { &mut self.base }
+
+        fn on_start(&mut self) {
+            self.log(String::from("start"));
+            if self.id().as_idx() == 0 {
+                self.send(self.channels()[0], 1);
+                let delay = SimTimeDelta::from(Duration::from_millis(10));
+                sim_sched().sched_self_event_with_data(delay, self.id(), Box::new(Tick));
+            }
+        }
+
+        fn on_message(&mut self, incoming_channel: ChannelId, msg: u32) {
+            self.log(format!("msg {}", msg));
+            if msg < 3 {
+                self.send(incoming_channel, msg + 1);
+            }
+        }
+
+        fn on_event(&mut self, _sender: ComponentId, event: Box<dyn Any>) {
+            assert!(event.is::<Tick>());
+            self.log(String::from("timer"));
+        }
+
+        fn terminate(&mut self) {}
+    }
+
+    struct PingBuilder(Log);
+
+    impl ComponentBuilder for PingBuilder {
+        fn build_component(&mut self, id: ComponentId) -> Box<dyn Component> {
+            Box::new(AsynchComponent::new(Ping { base: ComponentBase::new(id), log: self.0.clone() }))
+        }
+    }
+
+    #[test]
+    fn test_start_message_and_timer_callbacks() {
+        let log = Log::default();
+        let mut builder = PingBuilder(log.clone());
+        let mut simulation = Simulation::<DelayChannel>::default();
+        let a = simulation.add_component(&mut builder);
+        let b = simulation.add_component(&mut builder);
+        simulation.add_channel(&mut DelayChannelBuilder::with_delay(Duration::from_millis(2)), a, b);
+
+        simulation.call_init();
+        simulation.run().unwrap();
+
+        let expected = [(0, 0, "start"), (0, 1, "start"), (2, 1, "msg 1"), (4, 0, "msg 2"), (6, 1, "msg 3"), (10, 0, "timer")];
+        let expected: Vec<_> = expected.iter().map(|(t, id, what)| (*t, *id, String::from(*what))).collect();
+        assert_eq!(*log.borrow(), expected);
+    }
+
+    /// the oracle tells the other component its id, the components are not connected
+    struct Node {
+        base: ComponentBase,
+        oracle: bool,
+        received: Rc<RefCell<Vec<(SimTime, ChannelId, usize)>>>,
+    }
+
+    impl AsynchProcess for Node {
+        type Msg = usize;
+
+        fn get_sim_base(&self) -> &ComponentBase { &self.base }
+
+        fn get_sim_base_mut(&mut self) -> &mut ComponentBase { &mut self.base }
+
+        fn on_start(&mut self) {
+            if self.oracle {
+                self.send_direct(ComponentId::new(1), SimTimeDelta::from(Duration::from_millis(5)), self.id().as_idx());
+                // dropped by the receiver, it expects messages of type usize
+                self.send_direct(ComponentId::new(1), SimTimeDelta::from(Duration::from_millis(6)), String::from("hint"));
+            }
+        }
+
+        fn on_message(&mut self, incoming_channel: ChannelId, msg: usize) {
+            self.received.borrow_mut().push((sim_time(), incoming_channel, msg));
+        }
+
+        fn terminate(&mut self) {}
+    }
+
+    #[derive(Default)]
+    struct NodeBuilder {
+        received: Rc<RefCell<Vec<(SimTime, ChannelId, usize)>>>,
+    }
+
+    impl ComponentBuilder for NodeBuilder {
+        fn build_component(&mut self, id: ComponentId) -> Box<dyn Component> {
+            let oracle = id.as_idx() == 0;
+            Box::new(AsynchComponent::new(Node { base: ComponentBase::new(id), oracle, received: self.received.clone() }))
+        }
+    }
+
+    #[test]
+    fn test_send_direct() {
+        let mut builder = NodeBuilder::default();
+        let mut simulation = Simulation::<DelayChannel>::default();
+        simulation.add_component(&mut builder);
+        simulation.add_component(&mut builder);
+        simulation.enable_tracing();
+
+        simulation.call_init();
+        simulation.run().unwrap();
+
+        let received = builder.received.borrow();
+        assert_eq!(*received, [(SimTime::from(Duration::from_millis(5)), DIRECT_CHANNEL, 0)]);
+        assert!(received[0].1.is_direct());
+
+        let trace = simulation.trace().unwrap();
+        assert!(trace.records().iter().any(|r| matches!(r.kind, TraceEventKind::Receive { channel, msg_id: Some(_) } if channel.is_direct())));
+        assert_eq!(simulation.complexity().messages, 0);
+    }
+}
@@ -6,6 +6,12 @@ pub trait Channel {
                         source: ComponentId,
                         message: Box<dyn Any>,
     );
+
+    /// messages sent in one direction are delivered in the order they were sent,
+    /// snapshots need fifo channels
+    fn is_fifo(&self) -> bool {
+        true
+    }
 }
 
 pub trait ChannelBuilder {
@@ -1 +1,2 @@
-pub mod delay_channel;
\ No newline at end of file
+pub mod delay_channel;
+pub mod partial_synch_channel;
\ No newline at end of file
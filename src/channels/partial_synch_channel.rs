@@ -0,0 +1,194 @@
+use std::any::Any;
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::channel::{Channel, ChannelBuilder};
+use crate::keys::{ChannelId, ComponentId};
+use crate::simtime::{SimTime, SimTimeDelta};
+use crate::simvars::{sim_sched, sim_time};
+
+/// delays of messages sent before the global stabilization time
+#[derive(Debug, Copy, Clone)]
+pub enum PreGstDelay {
+    /// adversary holds every message until GST, it is delivered within delta after GST
+    Adversarial,
+    /// message is delayed by a random time up to GST + delta
+    Random,
+    /// message is delayed by a random time up to the bound, which may exceed delta
+    Bounded(SimTimeDelta),
+}
+
+/// channel of the partially synchronous model (Dwork, Lynch, Stockmeyer)
+///
+/// before the global stabilization time (GST) message delays are chosen by the adversary,
+/// messages sent after GST are delivered within delta. messages sent before GST are
+/// delivered by GST + delta at the latest. delays are random, so the channel is not FIFO
+#[derive(Debug)]
+pub struct PartialSynchChannel {
+    pub id: ChannelId,
+    pub left: ComponentId,
+    pub right: ComponentId,
+    pub gst: SimTime,
+    pub delta: SimTimeDelta,
+    pub pre_gst: PreGstDelay,
+    rng: StdRng,
+}
+
+impl PartialSynchChannel {
+    fn delay(&mut self, now: SimTime) -> SimTimeDelta {
+        let delta = self.delta.delta();
+
+        if now >= self.gst {
+            return SimTimeDelta::from(random_duration(&mut self.rng, delta));
+        }
+
+        // messages sent before GST have to arrive by GST + delta
+        let latest = (self.gst - now).delta() + delta;
+        let delay = match self.pre_gst {
+            PreGstDelay::Adversarial => (self.gst - now).delta() + random_duration(&mut self.rng, delta),
+            PreGstDelay::Random => random_duration(&mut self.rng, latest),
+            PreGstDelay::Bounded(bound) => random_duration(&mut self.rng, bound.delta()).min(latest),
+        };
+
+        SimTimeDelta::from(delay)
+    }
+}
+
+impl Channel for PartialSynchChannel {
+
+    fn accept_message_from(&mut self,
+                           source: ComponentId,
+                           message: Box<dyn Any>,
+    ) {
+        let dst: ComponentId;
+
+        if source == self.left {
+            dst = self.right;
+        } else if source == self.right {
+            dst = self.left;
+        } else {
+            panic!("unknown source {:?} for channel {:?}", source, self);
+        }
+
+        let delay = self.delay(sim_time());
+        sim_sched().sched_receive_msg(delay, dst, self.id, message);
+    }
+
+    fn is_fifo(&self) -> bool {
+        false
+    }
+}
+
+fn random_duration(rng: &mut StdRng, max: Duration) -> Duration {
+    Duration::from_nanos(rng.gen_range(0..=max.as_nanos() as u64))
+}
+
+pub struct PartialSynchChannelBuilder {
+    gst: SimTime,
+    delta: SimTimeDelta,
+    pre_gst: PreGstDelay,
+    seed: u64,
+}
+
+impl Default for PartialSynchChannelBuilder {
+    fn default() -> Self {
+        PartialSynchChannelBuilder {
+            gst: SimTime::default(),
+            delta: SimTimeDelta::from(Duration::from_millis(1)),
+            pre_gst: PreGstDelay::Random,
+            seed: 0,
+        }
+    }
+}
+
+impl ChannelBuilder for PartialSynchChannelBuilder {
+    type C = PartialSynchChannel;
+
+    fn build_channel(&self, id: ChannelId, p0: ComponentId, p1: ComponentId) -> Self::C {
+        PartialSynchChannel {
+            id,
+            left: p0,
+            right: p1,
+            gst: self.gst,
+            delta: self.delta,
+            pre_gst: self.pre_gst,
+            // each channel has its own stream of delays
+            rng: StdRng::seed_from_u64(self.seed.wrapping_add(id.as_idx() as u64)),
+        }
+    }
+}
+
+impl PartialSynchChannelBuilder {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn gst(&mut self, gst: Duration) -> &mut Self {
+        self.gst = SimTime::from(gst);
+        self
+    }
+
+    pub fn delta(&mut self, delta: Duration) -> &mut Self {
+        self.delta = SimTimeDelta::from(delta);
+        self
+    }
+
+    pub fn pre_gst_adversarial(&mut self) -> &mut Self {
+        self.pre_gst = PreGstDelay::Adversarial;
+        self
+    }
+
+    pub fn pre_gst_random(&mut self) -> &mut Self {
+        self.pre_gst = PreGstDelay::Random;
+        self
+    }
+
+    pub fn pre_gst_bound(&mut self, bound: Duration) -> &mut Self {
+        self.pre_gst = PreGstDelay::Bounded(SimTimeDelta::from(bound));
+        self
+    }
+
+    pub fn seed(&mut self, seed: u64) -> &mut Self {
+        self.seed = seed;
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const GST: Duration = Duration::from_millis(100);
+    const DELTA: Duration = Duration::from_millis(5);
+
+    fn channel(pre_gst: PreGstDelay) -> PartialSynchChannel {
+        let mut builder = PartialSynchChannelBuilder::new();
+        builder.gst(GST).delta(DELTA).seed(3);
+        builder.pre_gst = pre_gst;
+        builder.build_channel(ChannelId::new(0), ComponentId::new(0), ComponentId::new(1))
+    }
+
+    #[test]
+    fn test_delays_before_and_after_gst() {
+        let gst = SimTime::from(GST);
+        let pre_gst = [PreGstDelay::Adversarial, PreGstDelay::Random, PreGstDelay::Bounded(SimTimeDelta::from(GST * 3))];
+
+        for pre_gst in pre_gst {
+            let mut channel = channel(pre_gst);
+            for sent in (0..2 * GST.as_millis() as u64).map(|millis| SimTime::from(Duration::from_millis(millis))) {
+                let received = sent + channel.delay(sent);
+                if sent >= gst {
+                    assert!((received - sent).delta() <= DELTA, "{:?} sent at {:?}", pre_gst, sent);
+                } else {
+                    assert!(received <= gst + SimTimeDelta::from(DELTA), "{:?} sent at {:?}", pre_gst, sent);
+                }
+                if matches!(pre_gst, PreGstDelay::Adversarial) && sent < gst {
+                    assert!(received >= gst);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use crate::keys::ComponentId;
+
+/// lamport and vector time of a single event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClockStamp {
+    pub lamport: u64,
+    pub vector: Vec<u64>,
+}
+
+impl ClockStamp {
+    /// true if the event with this stamp causally precedes the event with `other` stamp
+    pub fn happened_before(&self, other: &ClockStamp) -> bool {
+        self.vector.iter().zip(other.vector.iter()).all(|(a, b)| a <= b)
+            && self.vector != other.vector
+    }
+
+    pub fn is_concurrent(&self, other: &ClockStamp) -> bool {
+        !self.happened_before(other) && !other.happened_before(self) && self != other
+    }
+}
+
+/// lamport and vector clocks of all components
+///
+/// clocks are maintained by the simulation if enabled with `Simulation::enable_logical_clocks()`,
+/// components can read them with `sim_clocks()`
+#[derive(Debug)]
+pub struct LogicalClocks {
+    lamport: Vec<u64>,
+    vector: Vec<Vec<u64>>,
+}
+
+impl LogicalClocks {
+    pub fn new(num_components: usize) -> Self {
+        LogicalClocks {
+            lamport: vec![0; num_components],
+            vector: vec![vec![0; num_components]; num_components],
+        }
+    }
+
+    pub fn lamport(&self, component: ComponentId) -> u64 {
+        self.lamport[component.as_idx()]
+    }
+
+    pub fn vector(&self, component: ComponentId) -> &[u64] {
+        &self.vector[component.as_idx()]
+    }
+
+    pub fn stamp(&self, component: ComponentId) -> ClockStamp {
+        ClockStamp {
+            lamport: self.lamport(component),
+            vector: self.vector(component).to_vec(),
+        }
+    }
+
+    /// local event or message send at the component
+    pub(crate) fn tick(&mut self, component: ComponentId) -> ClockStamp {
+        let idx = component.as_idx();
+        self.lamport[idx] += 1;
+        self.vector[idx][idx] += 1;
+        self.stamp(component)
+    }
+
+    /// message receive at the component, `sent` is the stamp of the send event
+    pub(crate) fn receive(&mut self, component: ComponentId, sent: &ClockStamp) -> ClockStamp {
+        let idx = component.as_idx();
+        self.lamport[idx] = std::cmp::max(self.lamport[idx], sent.lamport);
+
+        for (local, remote) in self.vector[idx].iter_mut().zip(sent.vector.iter()) {
+            *local = std::cmp::max(*local, *remote);
+        }
+
+        self.tick(component)
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_send_receive() {
+        let p0 = ComponentId::new(0);
+        let p1 = ComponentId::new(1);
+        let mut clocks = LogicalClocks::new(2);
+
+        clocks.tick(p1);
+        let sent = clocks.tick(p0);
+        let received = clocks.receive(p1, &sent);
+
+        assert_eq!(received.lamport, 2);
+        assert_eq!(received.vector, vec![1, 2]);
+        assert!(sent.happened_before(&received));
+        assert!(!received.happened_before(&sent));
+
+        let local = clocks.tick(p0);
+        assert!(local.is_concurrent(&received));
+    }
+}
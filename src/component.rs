@@ -1,8 +1,11 @@
 use crate::environment::Environment;
+use crate::gate::Gates;
+use crate::names::short_type_name;
+use crate::timer::Timers;
 use crate::keys::{ComponentId, ChannelId};
 use std::any::Any;
 
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum ChannelLabel {
     Left, Right
 }
@@ -11,13 +14,17 @@ pub enum ChannelLabel {
 pub struct ComponentBase {
     pub component_id: ComponentId,
     pub channels: Vec<ChannelId>,
+    pub gates: Gates,
+    pub timers: Timers,
 }
 
 impl ComponentBase {
     pub fn new(id: ComponentId) -> Self {
         ComponentBase {
             component_id: id,
-            channels: Vec::new()
+            channels: Vec::new(),
+            gates: Gates::new(),
+            timers: Timers::new(),
         }
     }
 
@@ -26,10 +33,32 @@ impl ComponentBase {
     }
 }
 
-pub trait Component {
+/// downcasting of components to their concrete type, implemented for every type
+pub trait AsAny: Any {
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Any> AsAny for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+pub trait Component: AsAny {
 
     fn sim_id(&self) -> ComponentId;
 
+    /// type of the component shown next to its name
+    fn type_name(&self) -> String {
+        short_type_name(std::any::type_name::<Self>())
+    }
+
     fn add_channel(&mut self, channel_id: ChannelId, label: ChannelLabel);
 
     fn init(&mut self);
@@ -49,6 +78,51 @@ pub trait Component {
     fn terminate(&mut self,
                  // env: &mut Environment
     );
+
+    /// true once the component has terminated or decided,
+    /// simulation stops when all components have terminated
+    fn is_terminated(&self) -> bool {
+        false
+    }
+
+    /// local state recorded by a global snapshot, `None` if the component does not take part
+    fn snapshot_state(&self) -> Option<Box<dyn Any>> {
+        None
+    }
+
+    /// process wrapped by the component, e.g. by `AsynchComponent`, see `Simulation::component`
+    fn inner(&self) -> Option<&dyn Any> {
+        None
+    }
+
+    fn inner_mut(&mut self) -> Option<&mut dyn Any> {
+        None
+    }
+
+    /// named gates, `None` if the component can only be connected with `Simulation::add_channel`
+    fn gates(&self) -> Option<&Gates> {
+        None
+    }
+
+    fn gates_mut(&mut self) -> Option<&mut Gates> {
+        None
+    }
+}
+
+/// component or the process wrapped by it as `T`
+pub(crate) fn downcast_ref<T: 'static>(component: &dyn Component) -> Option<&T> {
+    // dereference, `&dyn Component` itself implements `AsAny`
+    match (*component).as_any().downcast_ref::<T>() {
+        Some(component) => Some(component),
+        None => component.inner().and_then(|inner| inner.downcast_ref::<T>()),
+    }
+}
+
+pub(crate) fn downcast_mut<T: 'static>(component: &mut dyn Component) -> Option<&mut T> {
+    if (*component).as_any().is::<T>() {
+        return (*component).as_any_mut().downcast_mut::<T>();
+    }
+    component.inner_mut().and_then(|inner| inner.downcast_mut::<T>())
 }
 
 pub trait StaticComponentBuilder {
@@ -59,4 +133,75 @@ pub trait StaticComponentBuilder {
 
 pub trait ComponentBuilder {
     fn build_component(&mut self, id: ComponentId) -> Box<dyn Component>;
-}
\ No newline at end of file
+
+    /// name of the component, called after `build_component`, `None` names it `c<idx>`
+    fn component_name(&mut self, _id: ComponentId) -> Option<String> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::asynch::process::{AsynchComponent, AsynchProcess};
+    use crate::channels::delay_channel::{DelayChannel, DelayChannelBuilder};
+    use crate::sim::Simulation;
+
+    /// node 0 sends one message to node 1
+    struct Counter {
+        base: ComponentBase,
+        received: usize,
+    }
+
+    impl AsynchProcess for Counter {
+        type Msg = ();
+
+        fn get_sim_base(&self) -> &ComponentBase { &self.base }
+
+        fn get_sim_base_mut(&mut self) -> &mut ComponentBase { &mut self.base }
+
+        fn on_start(&mut self) {
+            if self.id().as_idx() == 0 {
+                self.send(self.channels()[0], ());
+            }
+        }
+
+        fn on_message(&mut self, _incoming_channel: ChannelId, _msg: ()) {
+            self.received += 1;
+        }
+
+        fn terminate(&mut self) {}
+    }
+
+    /// some other component type
+    struct Other;
+
+    struct CounterBuilder;
+
+    impl ComponentBuilder for CounterBuilder {
+        fn build_component(&mut self, id: ComponentId) -> Box<dyn Component> {
+            Box::new(AsynchComponent::new(Counter { base: ComponentBase::new(id), received: 0 }))
+        }
+    }
+
+    #[test]
+    fn test_downcast_wrapped_processes() {
+        let mut simulation = Simulation::<DelayChannel>::default();
+        let a = simulation.add_component(&mut CounterBuilder);
+        let b = simulation.add_component(&mut CounterBuilder);
+        simulation.add_channel(&mut DelayChannelBuilder::with_delay(Duration::from_millis(1)), a, b);
+
+        simulation.call_init();
+        simulation.run().unwrap();
+
+        assert_eq!(simulation.component::<Counter>(b).map(|c| c.received), Some(1));
+        assert!(simulation.component::<AsynchComponent<Counter>>(a).is_some());
+        assert!(simulation.component::<Other>(a).is_none());
+
+        simulation.component_mut::<Counter>(a).unwrap().received = 5;
+        let received: Vec<(ComponentId, usize)> = simulation.components_of::<Counter>().map(|(id, c)| (id, c.received)).collect();
+        assert_eq!(received, [(a, 5), (b, 1)]);
+    }
+}
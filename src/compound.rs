@@ -0,0 +1,156 @@
+use crate::component::{Component, ComponentBuilder};
+use crate::gate::Gate;
+use crate::keys::{ChannelId, ComponentId};
+
+/// builds the submodules of a compound component and links between them
+pub trait CompoundBuilder {
+    fn build_compound(&mut self, module: &mut Module);
+}
+
+/// compound component under construction
+///
+/// submodules are ordinary components of the simulation, links are zero-delay channels
+/// between submodules. messages on links go through the scheduler like any other message,
+/// so every submodule shows up in traces on its own
+pub struct Module {
+    next_component: usize,
+    next_channel: usize,
+    pub(crate) components: Vec<Box<dyn Component>>,
+    pub(crate) links: Vec<(ComponentId, ComponentId)>,
+    pub(crate) link_gates: Vec<(ChannelId, Gate)>,
+    submodules: Vec<(String, ComponentId)>,
+    gate: Option<ComponentId>,
+}
+
+impl Module {
+    pub(crate) fn new(next_component: usize, next_channel: usize) -> Self {
+        Module {
+            next_component,
+            next_channel,
+            components: Vec::new(),
+            links: Vec::new(),
+            link_gates: Vec::new(),
+            submodules: Vec::new(),
+            gate: None,
+        }
+    }
+
+    pub fn add_submodule(&mut self, name: &str, builder: &mut dyn ComponentBuilder) -> ComponentId {
+        let id = ComponentId::new(self.next_component + self.components.len());
+        self.components.push(builder.build_component(id));
+        self.submodules.push((String::from(name), id));
+        id
+    }
+
+    /// adds a compound submodule, its submodules are named `name.submodule`
+    pub fn add_compound(&mut self, name: &str, builder: &mut dyn CompoundBuilder) -> Compound {
+        let mut module = Module::new(self.next_component + self.components.len(),
+                                     self.next_channel + self.links.len());
+        builder.build_compound(&mut module);
+        let compound = module.compound(name);
+
+        self.components.append(&mut module.components);
+        self.links.append(&mut module.links);
+        self.link_gates.append(&mut module.link_gates);
+        self.submodules.extend(compound.submodules.iter().map(|(sub, id)| (format!("{}.{}", name, sub), *id)));
+        compound
+    }
+
+    /// links two submodules, `left` gets the link with `ChannelLabel::Left`
+    pub fn link(&mut self, left: ComponentId, right: ComponentId) -> ChannelId {
+        self.links.push((left, right));
+        ChannelId::new(self.next_channel + self.links.len() - 1)
+    }
+
+    /// links gates of two submodules, see `Simulation::connect`
+    pub fn connect(&mut self, a: Gate, b: Gate) -> ChannelId {
+        let channel_id = self.link(a.component, b.component);
+        self.link_gates.push((channel_id, a));
+        self.link_gates.push((channel_id, b));
+        channel_id
+    }
+
+    /// submodule that gets the channels connecting the compound to other components,
+    /// defaults to the first submodule
+    pub fn set_gate(&mut self, submodule: ComponentId) {
+        self.gate = Some(submodule);
+    }
+
+    pub(crate) fn compound(&self, name: &str) -> Compound {
+        let gate = self.gate
+            .or_else(|| self.submodules.first().map(|(_, id)| *id))
+            .unwrap_or_else(|| panic!("compound {} has no submodules", name));
+
+        Compound {
+            name: String::from(name),
+            gate,
+            submodules: self.submodules.clone(),
+            links: (0..self.links.len()).map(|idx| ChannelId::new(self.next_channel + idx)).collect(),
+        }
+    }
+}
+
+/// compound component added to the simulation
+#[derive(Debug, Clone)]
+pub struct Compound {
+    pub name: String,
+    /// connect channels from other components to the gate
+    pub gate: ComponentId,
+    submodules: Vec<(String, ComponentId)>,
+    pub links: Vec<ChannelId>,
+}
+
+impl Compound {
+    /// submodule by its name, submodules of nested compounds are named `compound.submodule`
+    pub fn submodule(&self, name: &str) -> Option<ComponentId> {
+        self.submodules.iter().find(|(n, _)| n == name).map(|(_, id)| *id)
+    }
+
+    pub fn submodules(&self) -> &[(String, ComponentId)] {
+        &self.submodules
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::channels::delay_channel::{DelayChannel, DelayChannelBuilder};
+    use crate::dummycomponent::DummyComponentBuilder;
+    use crate::sim::Simulation;
+
+    struct Layers;
+
+    impl CompoundBuilder for Layers {
+        fn build_compound(&mut self, module: &mut Module) {
+            let l2 = module.add_submodule("l2", &mut DummyComponentBuilder::default());
+            let l3 = module.add_submodule("l3", &mut DummyComponentBuilder::default());
+            module.link(l2, l3);
+        }
+    }
+
+    struct Router;
+
+    impl CompoundBuilder for Router {
+        fn build_compound(&mut self, module: &mut Module) {
+            let control = module.add_submodule("control", &mut DummyComponentBuilder::default());
+            let stack = module.add_compound("stack", &mut Layers);
+            module.link(control, stack.submodule("l3").unwrap());
+            module.set_gate(stack.gate);
+        }
+    }
+
+    #[test]
+    fn test_nested_compound_ids() {
+        let mut simulation = Simulation::<DelayChannel>::default();
+        let r0 = simulation.add_compound("r0", &mut Router);
+        let r1 = simulation.add_compound("r1", &mut Router);
+        let wire = simulation.add_channel(&mut DelayChannelBuilder::new(), r0.gate, r1.gate);
+
+        let names: Vec<&str> = r1.submodules().iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["control", "stack.l2", "stack.l3"]);
+        assert_eq!(r1.submodule("stack.l2"), Some(r1.gate));
+        assert_eq!(r1.gate.as_idx(), 4);
+        assert_eq!(r1.links.iter().map(|l| l.as_idx()).collect::<Vec<_>>(), [2, 3]);
+        assert_eq!(wire.as_idx(), 4);
+    }
+}
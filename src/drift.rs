@@ -0,0 +1,142 @@
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::simtime::{SimTime, SimTimeDelta};
+use crate::simvars::sim_time;
+
+/// local clock of a component running at a constant rate relative to simulation time,
+/// see `Simulation::enable_clock_drift` and `sim_local_clock`
+#[derive(Debug, Copy, Clone)]
+pub struct DriftingClock {
+    rate: f64,
+}
+
+impl Default for DriftingClock {
+    fn default() -> Self {
+        DriftingClock { rate: 1.0 }
+    }
+}
+
+impl DriftingClock {
+    pub fn new(rate: f64) -> Self {
+        assert!(rate > 0.0, "clock rate must be positive");
+        DriftingClock { rate }
+    }
+
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    /// local time at the current simulation time
+    pub fn now(&self) -> SimTime {
+        self.local_time(sim_time())
+    }
+
+    pub fn local_time(&self, time: SimTime) -> SimTime {
+        SimTime::from(time.time().mul_f64(self.rate))
+    }
+
+    /// converts a duration measured by the local clock to simulation time,
+    /// e.g. to schedule a local timeout
+    pub fn to_sim_delta(&self, local: Duration) -> SimTimeDelta {
+        SimTimeDelta::from(local.div_f64(self.rate))
+    }
+}
+
+/// generates local clocks with rates in `[1 - max_drift, 1 + max_drift]`,
+/// so the relative drift of any two clocks is bounded
+pub struct ClockDriftModel {
+    max_drift: f64,
+    rng: StdRng,
+}
+
+impl ClockDriftModel {
+    pub fn new(max_drift: f64, seed: u64) -> Self {
+        assert!((0.0..1.0).contains(&max_drift), "drift has to be in [0, 1)");
+        ClockDriftModel { max_drift, rng: StdRng::seed_from_u64(seed) }
+    }
+
+    pub fn next_clock(&mut self) -> DriftingClock {
+        if self.max_drift == 0.0 {
+            return DriftingClock::default();
+        }
+
+        DriftingClock::new(1.0 + self.rng.gen_range(-self.max_drift..=self.max_drift))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::any::Any;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::asynch::process::{AsynchComponent, AsynchProcess};
+    use crate::channels::partial_synch_channel::PartialSynchChannel;
+    use crate::component::{Component, ComponentBase, ComponentBuilder};
+    use crate::keys::{ChannelId, ComponentId};
+    use crate::sim::Simulation;
+    use crate::simvars::{sim_local_clock, sim_sched};
+
+    type LocalTimes = Rc<RefCell<Vec<SimTime>>>;
+
+    /// measures one second on its local clock
+    struct Node {
+        base: ComponentBase,
+        local_times: LocalTimes,
+    }
+
+    impl AsynchProcess for Node {
+        type Msg = ();
+
+        fn get_sim_base(&self) -> &ComponentBase { &self.base }
+
+        fn get_sim_base_mut(&mut self) -> &mut ComponentBase { &mut self.base }
+
+        fn on_start(&mut self) {
+            let delay = sim_local_clock(self.id()).to_sim_delta(Duration::from_secs(1));
+            sim_sched().sched_self_event_with_data(delay, self.id(), Box::new(()));
+        }
+
+        fn on_message(&mut self, _incoming_channel: ChannelId, _msg: ()) {}
+
+        fn on_event(&mut self, _sender: ComponentId, _event: Box<dyn Any>) {
+            self.local_times.borrow_mut().push(self.local_time());
+        }
+
+        fn terminate(&mut self) {}
+    }
+
+    struct NodeBuilder(LocalTimes);
+
+    impl ComponentBuilder for NodeBuilder {
+        fn build_component(&mut self, id: ComponentId) -> Box<dyn Component> {
+            Box::new(AsynchComponent::new(Node { base: ComponentBase::new(id), local_times: self.0.clone() }))
+        }
+    }
+
+    #[test]
+    fn test_local_clocks_drift() {
+        let local_times = LocalTimes::default();
+        let mut builder = NodeBuilder(local_times.clone());
+        let mut simulation = Simulation::<PartialSynchChannel>::default();
+        for _ in 0..4 {
+            simulation.add_component(&mut builder);
+        }
+        simulation.enable_clock_drift(0.1, 7);
+        simulation.call_init();
+        let rates: Vec<f64> = (0..4).map(|idx| sim_local_clock(ComponentId::new(idx)).rate()).collect();
+        simulation.run().unwrap();
+
+        assert!(rates.iter().all(|rate| (0.9..=1.1).contains(rate)));
+        assert!(rates.windows(2).any(|w| w[0] != w[1]));
+        assert_eq!(local_times.borrow().len(), 4);
+        for local in local_times.borrow().iter() {
+            let error = local.time().as_secs_f64() - 1.0;
+            assert!(error.abs() < 1e-6, "{:?}", local);
+        }
+    }
+}
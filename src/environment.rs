@@ -1,19 +1,121 @@
 use std::collections::HashMap;
 use std::any::Any;
+use std::marker::PhantomData;
 
-// TODO:
+/// key of a value of type `T`, e.g. `const TOPOLOGY: Key<TopologyInfo> = Key::new("topology")`
+pub struct Key<T> {
+    name: &'static str,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Key<T> {
+    pub const fn new(name: &'static str) -> Self {
+        Key { name, _marker: PhantomData }
+    }
+}
+
+impl<T> Clone for Key<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Key<T> {}
+
+/// key of an environment value, strings for any type and `Key<T>` for values of type `T`
+pub trait EnvKey<T> {
+    fn name(&self) -> &str;
+}
+
+impl<T> EnvKey<T> for &str {
+    fn name(&self) -> &str {
+        self
+    }
+}
+
+impl<T> EnvKey<T> for Key<T> {
+    fn name(&self) -> &str {
+        self.name
+    }
+}
+
+impl<T> EnvKey<T> for &Key<T> {
+    fn name(&self) -> &str {
+        self.name
+    }
+}
 
-#[derive(Debug)]
+/// values shared by the simulation, e.g. the topology, global parameters and oracle data
+///
+/// builders publish values with `Simulation::env_mut()` before `call_init`,
+/// components and invariants read them with `sim_env()` during the run
+#[derive(Debug, Default)]
 pub struct Environment {
     store: Vec<Box<dyn Any>>,
     keys: HashMap<String, usize>
 }
 
-impl Default for Environment {
-    fn default() -> Self {
-        Environment {
-            store: Vec::new(),
-            keys: HashMap::new(),
+impl Environment {
+    /// stores the value, returns the previous value if it had the same type
+    pub fn insert<T: 'static>(&mut self, key: impl EnvKey<T>, value: T) -> Option<T> {
+        match self.keys.get(key.name()) {
+            Some(idx) => {
+                let previous = std::mem::replace(&mut self.store[*idx], Box::new(value));
+                previous.downcast::<T>().ok().map(|previous| *previous)
+            }
+            None => {
+                self.keys.insert(String::from(key.name()), self.store.len());
+                self.store.push(Box::new(value));
+                None
+            }
         }
     }
-}
\ No newline at end of file
+
+    /// value of the key, `None` if the key is missing or the value has another type
+    pub fn get<T: 'static>(&self, key: impl EnvKey<T>) -> Option<&T> {
+        self.keys.get(key.name()).and_then(|idx| self.store[*idx].downcast_ref::<T>())
+    }
+
+    pub fn get_mut<T: 'static>(&mut self, key: impl EnvKey<T>) -> Option<&mut T> {
+        let idx = *self.keys.get(key.name())?;
+        self.store[idx].downcast_mut::<T>()
+    }
+
+    /// true if the key has a value of type `T`
+    pub fn contains<T: 'static>(&self, key: impl EnvKey<T>) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ROUNDS: Key<u32> = Key::new("rounds");
+
+    #[test]
+    fn test_typed_values() {
+        let mut env = Environment::default();
+        assert_eq!(env.insert(ROUNDS, 3), None);
+        env.insert("name", String::from("ring"));
+
+        *env.get_mut(ROUNDS).unwrap() += 1;
+        assert_eq!(env.get(ROUNDS), Some(&4));
+        assert_eq!(env.get::<String>("name").map(|s| s.as_str()), Some("ring"));
+        assert_eq!(env.get::<u64>("rounds"), None);
+        assert!(env.contains(ROUNDS) && env.contains::<String>("name"));
+        assert!(!env.contains::<u64>("rounds") && !env.contains::<String>("topology"));
+
+        assert_eq!(env.insert("rounds", 7u32), Some(4));
+        assert_eq!(env.insert("rounds", 1.5f64), None);
+        assert_eq!(env.len(), 2);
+    }
+}
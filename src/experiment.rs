@@ -0,0 +1,7 @@
+pub use self::design::Design;
+pub use self::param::{ParamRange, ParamValue};
+pub use self::runner::{Experiment, ExperimentResults, RunConfig, RunResult};
+
+pub mod param;
+pub mod design;
+pub mod runner;
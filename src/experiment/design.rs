@@ -0,0 +1,112 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::experiment::param::{ParamRange, ParamValue};
+
+/// how the design points of an experiment are chosen from parameter ranges
+#[derive(Debug, Clone, Default)]
+pub enum Design {
+    /// every combination of the levels of all parameters
+    #[default]
+    Cartesian,
+    /// `samples` points, each parameter range is split into `samples` strata
+    /// and every stratum is used exactly once
+    LatinHypercube { samples: usize, seed: u64 },
+}
+
+impl Design {
+    /// returns design points, each point holds one value per range (in the order of `ranges`)
+    pub fn points(&self, ranges: &[ParamRange]) -> Vec<Vec<ParamValue>> {
+        match self {
+            Design::Cartesian => cartesian(ranges),
+            Design::LatinHypercube { samples, seed } => latin_hypercube(ranges, *samples, *seed),
+        }
+    }
+}
+
+fn cartesian(ranges: &[ParamRange]) -> Vec<Vec<ParamValue>> {
+    let mut points = vec![Vec::with_capacity(ranges.len())];
+
+    for range in ranges {
+        let levels = range.levels();
+        let mut next = Vec::with_capacity(points.len() * levels.len());
+
+        for point in &points {
+            for level in &levels {
+                let mut p = point.clone();
+                p.push(*level);
+                next.push(p);
+            }
+        }
+
+        points = next;
+    }
+
+    points
+}
+
+fn latin_hypercube(ranges: &[ParamRange], samples: usize, seed: u64) -> Vec<Vec<ParamValue>> {
+    assert!(samples > 0, "latin hypercube needs at least one sample");
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut points = vec![Vec::with_capacity(ranges.len()); samples];
+
+    for range in ranges {
+        let mut strata: Vec<usize> = (0..samples).collect();
+        strata.shuffle(&mut rng);
+
+        for (point, stratum) in points.iter_mut().zip(strata) {
+            let u = (stratum as f64 + rng.gen::<f64>()) / samples as f64;
+            // guard against rounding up to the upper bound
+            point.push(range.sample(u.min(1.0 - f64::EPSILON)));
+        }
+    }
+
+    points
+}
+
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_cartesian() {
+        let ranges = vec![
+            ParamRange::ints(10, 30, 10),
+            ParamRange::floats(0.1, 0.2, 2),
+        ];
+
+        let points = Design::Cartesian.points(&ranges);
+        assert_eq!(points.len(), 6);
+        assert_eq!(points[0], vec![ParamValue::Int(10), ParamValue::Float(0.1)]);
+        assert_eq!(points[5], vec![ParamValue::Int(30), ParamValue::Float(0.2)]);
+    }
+
+    #[test]
+    fn test_latin_hypercube_strata() {
+        const SAMPLES: usize = 8;
+        let ranges = vec![
+            ParamRange::floats(0.0, 8.0, 2),
+            ParamRange::ints(0, 7, 1),
+        ];
+
+        let design = Design::LatinHypercube { samples: SAMPLES, seed: 42 };
+        let points = design.points(&ranges);
+        assert_eq!(points.len(), SAMPLES);
+
+        // each stratum of each parameter is hit exactly once
+        for dim in 0..ranges.len() {
+            let mut strata: Vec<usize> = points.iter()
+                .map(|p| p[dim].as_float().floor() as usize)
+                .collect();
+            strata.sort_unstable();
+            assert_eq!(strata, (0..SAMPLES).collect::<Vec<_>>());
+        }
+
+        // same seed, same design
+        assert_eq!(points, design.points(&ranges));
+    }
+}
@@ -0,0 +1,110 @@
+use std::fmt;
+
+/// value of a single experiment parameter
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ParamValue {
+    Int(i64),
+    Float(f64),
+}
+
+impl ParamValue {
+    pub fn as_int(&self) -> i64 {
+        match *self {
+            ParamValue::Int(v) => v,
+            ParamValue::Float(v) => v.round() as i64,
+        }
+    }
+
+    pub fn as_usize(&self) -> usize {
+        let v = self.as_int();
+        assert!(v >= 0, "parameter value {} can not be used as usize", v);
+        v as usize
+    }
+
+    pub fn as_float(&self) -> f64 {
+        match *self {
+            ParamValue::Int(v) => v as f64,
+            ParamValue::Float(v) => v,
+        }
+    }
+}
+
+impl fmt::Display for ParamValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamValue::Int(v) => write!(f, "{}", v),
+            ParamValue::Float(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+/// range of values a parameter takes in an experiment
+///
+/// cartesian designs use the discrete levels of the range,
+/// latin hypercube designs sample the range between its first and last level
+#[derive(Debug, Clone)]
+pub enum ParamRange {
+    /// explicit list of levels
+    Values(Vec<ParamValue>),
+    /// integers `from..=to` with the given step
+    Ints { from: i64, to: i64, step: i64 },
+    /// `levels` evenly spaced floats between `from` and `to` (both included)
+    Floats { from: f64, to: f64, levels: usize },
+}
+
+impl ParamRange {
+    pub fn ints(from: i64, to: i64, step: i64) -> Self {
+        assert!(step > 0, "step must be positive");
+        assert!(from <= to, "empty integer range {}..={}", from, to);
+        ParamRange::Ints { from, to, step }
+    }
+
+    pub fn floats(from: f64, to: f64, levels: usize) -> Self {
+        assert!(levels > 0, "float range needs at least one level");
+        ParamRange::Floats { from, to, levels }
+    }
+
+    pub fn values(values: Vec<ParamValue>) -> Self {
+        assert!(!values.is_empty(), "parameter needs at least one value");
+        ParamRange::Values(values)
+    }
+
+    /// discrete levels of the range, in increasing order for generated ranges
+    pub fn levels(&self) -> Vec<ParamValue> {
+        match self {
+            ParamRange::Values(values) => values.clone(),
+            ParamRange::Ints { from, to, step } => {
+                (*from..=*to).step_by(*step as usize).map(ParamValue::Int).collect()
+            }
+            ParamRange::Floats { from, to, levels } => {
+                if *levels == 1 {
+                    return vec![ParamValue::Float(*from)];
+                }
+                let step = (to - from) / (*levels - 1) as f64;
+                (0..*levels).map(|i| ParamValue::Float(from + step * i as f64)).collect()
+            }
+        }
+    }
+
+    /// maps `u` from `[0, 1)` onto the range
+    ///
+    /// integer ranges are rounded down to a multiple of the step,
+    /// explicit value lists pick the level with the matching index
+    pub fn sample(&self, u: f64) -> ParamValue {
+        assert!((0.0..1.0).contains(&u), "sample point {} outside of [0, 1)", u);
+
+        match self {
+            ParamRange::Values(values) => {
+                values[(u * values.len() as f64) as usize]
+            }
+            ParamRange::Ints { from, to, step } => {
+                let num_levels = (to - from) / step + 1;
+                let level = (u * num_levels as f64) as i64;
+                ParamValue::Int(from + level * step)
+            }
+            ParamRange::Floats { from, to, .. } => {
+                ParamValue::Float(from + u * (to - from))
+            }
+        }
+    }
+}
@@ -0,0 +1,239 @@
+use std::io;
+use std::io::Write;
+
+use crate::experiment::design::Design;
+use crate::experiment::param::{ParamRange, ParamValue};
+
+/// parameters of a single run of an experiment
+#[derive(Debug, Clone)]
+pub struct RunConfig {
+    pub run: usize,
+    pub seed: u64,
+    params: Vec<(String, ParamValue)>,
+}
+
+impl RunConfig {
+    pub fn get(&self, name: &str) -> ParamValue {
+        self.params.iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| *v)
+            .unwrap_or_else(|| panic!("unknown parameter {}", name))
+    }
+
+    pub fn get_int(&self, name: &str) -> i64 {
+        self.get(name).as_int()
+    }
+
+    pub fn get_usize(&self, name: &str) -> usize {
+        self.get(name).as_usize()
+    }
+
+    pub fn get_float(&self, name: &str) -> f64 {
+        self.get(name).as_float()
+    }
+
+    pub fn params(&self) -> &[(String, ParamValue)] {
+        &self.params
+    }
+}
+
+/// named values measured in a single run
+#[derive(Debug, Clone, Default)]
+pub struct RunResult {
+    values: Vec<(String, f64)>,
+}
+
+impl RunResult {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, name: &str, value: f64) -> &mut Self {
+        match self.values.iter_mut().find(|(n, _)| n == name) {
+            Some((_, v)) => *v = value,
+            None => self.values.push((String::from(name), value)),
+        }
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<f64> {
+        self.values.iter().find(|(n, _)| n == name).map(|(_, v)| *v)
+    }
+
+    pub fn values(&self) -> &[(String, f64)] {
+        &self.values
+    }
+}
+
+/// parameter sweep over a set of parameter ranges and seeds
+///
+/// every design point is run once for each seed
+pub struct Experiment {
+    params: Vec<(String, ParamRange)>,
+    seeds: Vec<u64>,
+    design: Design,
+}
+
+impl Default for Experiment {
+    fn default() -> Self {
+        Experiment {
+            params: Vec::new(),
+            seeds: vec![0],
+            design: Design::Cartesian,
+        }
+    }
+}
+
+impl Experiment {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn param(&mut self, name: &str, range: ParamRange) -> &mut Self {
+        assert!(self.params.iter().all(|(n, _)| n != name), "duplicate parameter {}", name);
+        self.params.push((String::from(name), range));
+        self
+    }
+
+    pub fn seeds(&mut self, seeds: Vec<u64>) -> &mut Self {
+        assert!(!seeds.is_empty(), "experiment needs at least one seed");
+        self.seeds = seeds;
+        self
+    }
+
+    pub fn design(&mut self, design: Design) -> &mut Self {
+        self.design = design;
+        self
+    }
+
+    /// returns configurations of all runs of the experiment
+    pub fn configs(&self) -> Vec<RunConfig> {
+        let ranges: Vec<ParamRange> = self.params.iter().map(|(_, r)| r.clone()).collect();
+        let mut configs = Vec::new();
+
+        for point in self.design.points(&ranges) {
+            for seed in &self.seeds {
+                let params = self.params.iter()
+                    .zip(point.iter())
+                    .map(|((name, _), value)| (name.clone(), *value))
+                    .collect();
+
+                configs.push(RunConfig { run: configs.len(), seed: *seed, params });
+            }
+        }
+
+        configs
+    }
+
+    /// calls `run` for every configuration and collects the results
+    pub fn run<F>(&self, mut run: F) -> ExperimentResults
+        where F: FnMut(&RunConfig) -> RunResult
+    {
+        let configs = self.configs();
+        let mut results = ExperimentResults {
+            param_names: self.params.iter().map(|(n, _)| n.clone()).collect(),
+            rows: Vec::with_capacity(configs.len()),
+        };
+
+        for config in configs {
+            let result = run(&config);
+            results.rows.push((config, result));
+        }
+
+        results
+    }
+}
+
+/// results of all runs of an experiment, one row per run
+pub struct ExperimentResults {
+    param_names: Vec<String>,
+    rows: Vec<(RunConfig, RunResult)>,
+}
+
+impl ExperimentResults {
+    pub fn rows(&self) -> &[(RunConfig, RunResult)] {
+        &self.rows
+    }
+
+    /// names of all result values in the order they were first recorded
+    pub fn value_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for (_, result) in &self.rows {
+            for (name, _) in result.values() {
+                if !names.contains(name) {
+                    names.push(name.clone());
+                }
+            }
+        }
+        names
+    }
+
+    /// writes results as csv with columns `run`, `seed`, parameters and result values
+    ///
+    /// values a run did not record are left empty
+    pub fn write_csv<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        let value_names = self.value_names();
+
+        let header: Vec<String> = ["run", "seed"].iter()
+            .map(|s| String::from(*s))
+            .chain(self.param_names.iter().cloned())
+            .chain(value_names.iter().cloned())
+            .map(|s| csv_field(&s))
+            .collect();
+        writeln!(w, "{}", header.join(","))?;
+
+        for (config, result) in &self.rows {
+            let mut row = vec![config.run.to_string(), config.seed.to_string()];
+            row.extend(config.params.iter().map(|(_, v)| v.to_string()));
+            row.extend(value_names.iter().map(|name| {
+                result.get(name).map(|v| v.to_string()).unwrap_or_default()
+            }));
+            writeln!(w, "{}", row.join(","))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut buf = Vec::new();
+        self.write_csv(&mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        String::from(s)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sweep_to_csv() {
+        let mut experiment = Experiment::new();
+        experiment.param("nodes", ParamRange::ints(2, 4, 2)).seeds(vec![1, 7]);
+
+        let results = experiment.run(|config| {
+            let mut result = RunResult::new();
+            result.record("messages", (config.get_usize("nodes") as u64 * config.seed) as f64);
+            if config.seed == 7 {
+                result.record("rounds, max", 3.5);
+            }
+            result
+        });
+
+        assert_eq!(results.rows().len(), 4);
+        assert_eq!(results.to_csv(), concat!(
+            "run,seed,nodes,messages,\"rounds, max\"\n",
+            "0,1,2,2,\n",
+            "1,7,2,14,3.5\n",
+            "2,1,4,4,\n",
+            "3,7,4,28,3.5\n",
+        ));
+    }
+}
@@ -0,0 +1,6 @@
+pub use self::explorer::{ChannelOrder, Counterexample, ExplorationResult, Explorer};
+pub use self::state::Transition;
+pub(crate) use self::explorer::panic_message;
+
+mod explorer;
+mod state;
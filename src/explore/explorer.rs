@@ -0,0 +1,384 @@
+use std::any::Any;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+use crate::component::{ChannelLabel, Component};
+use crate::explore::state::{Pending, State, Transition};
+use crate::keys::{ChannelId, ComponentId};
+use crate::scheduler::{DirectMessage, EventType};
+use crate::simvars::{init_scheduler, sim_sched};
+
+/// which messages of a channel can be delivered next
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ChannelOrder {
+    /// messages are delivered in the order they were sent on each channel direction
+    Fifo,
+    /// any message in transit can be delivered next
+    Unordered,
+}
+
+/// execution that leads to a state violating an invariant
+#[derive(Debug, Clone)]
+pub struct Counterexample {
+    /// name of the violated invariant, `panic` if a component panicked
+    pub invariant: String,
+    /// panic message or error reported by a component
+    pub message: Option<String>,
+    pub steps: Vec<Transition>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExplorationResult {
+    /// number of distinct states visited
+    pub states: usize,
+    pub transitions: usize,
+    pub max_depth: usize,
+    /// exploration was cut off by the depth or state bound
+    pub bound_reached: bool,
+    pub violation: Option<Counterexample>,
+}
+
+type StateInvariant<C> = (String, Box<dyn Fn(&[C]) -> bool>);
+
+/// explores all interleavings of messages and events of a small system
+///
+/// instead of running the single execution fixed by the scheduler, the explorer executes
+/// every enabled message delivery and event in every reachable state (breadth first), so a
+/// violation is reported with a shortest counterexample. time is abstracted away: pending
+/// events and messages can be executed in any order regardless of their delays.
+///
+/// all components have the type `C`, messages sent by components have the type `M` and
+/// events (timers, start events, ...) the type `E`. visited states are stored and
+/// deduplicated by hash and equality of components and pending messages and events.
+///
+/// processes wrapped in `AsynchComponent` can not be explored, their start and timeout
+/// events are private and neither `Clone` nor `Hash`
+pub struct Explorer<C, M, E = ()> {
+    components: Vec<C>,
+    channels: Vec<(ComponentId, ComponentId)>,
+    invariants: Vec<StateInvariant<C>>,
+    order: ChannelOrder,
+    max_depth: usize,
+    max_states: usize,
+    _marker: PhantomData<(M, E)>,
+}
+
+impl<C, M, E> Default for Explorer<C, M, E> {
+    fn default() -> Self {
+        Explorer {
+            components: Vec::new(),
+            channels: Vec::new(),
+            invariants: Vec::new(),
+            order: ChannelOrder::Fifo,
+            max_depth: usize::MAX,
+            max_states: usize::MAX,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<C, M, E> Explorer<C, M, E>
+    where C: Component + Clone + Hash + Eq + 'static,
+          M: Clone + Hash + Eq + Debug + 'static,
+          E: Clone + Hash + Eq + Debug + 'static,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_component<F>(&mut self, build: F) -> ComponentId
+        where F: FnOnce(ComponentId) -> C
+    {
+        let id = ComponentId::new(self.components.len());
+        self.components.push(build(id));
+        id
+    }
+
+    pub fn add_channel(&mut self, left: ComponentId, right: ComponentId) -> ChannelId {
+        let channel_id = ChannelId::new(self.channels.len());
+        self.channels.push((left, right));
+        self.components[left.as_idx()].add_channel(channel_id, ChannelLabel::Left);
+        self.components[right.as_idx()].add_channel(channel_id, ChannelLabel::Right);
+        channel_id
+    }
+
+    pub fn add_invariant<F>(&mut self, name: &str, check: F) -> &mut Self
+        where F: Fn(&[C]) -> bool + 'static
+    {
+        self.invariants.push((String::from(name), Box::new(check)));
+        self
+    }
+
+    pub fn channel_order(&mut self, order: ChannelOrder) -> &mut Self {
+        self.order = order;
+        self
+    }
+
+    pub fn max_depth(&mut self, depth: usize) -> &mut Self {
+        self.max_depth = depth;
+        self
+    }
+
+    pub fn max_states(&mut self, states: usize) -> &mut Self {
+        self.max_states = states;
+        self
+    }
+
+    /// explores on a new scheduler of the current thread, do not call it while a simulation
+    /// on this thread is in use, see `init_scheduler`
+    pub fn explore(&self) -> ExplorationResult {
+        init_scheduler();
+
+        let mut result = ExplorationResult {
+            states: 1,
+            transitions: 0,
+            max_depth: 0,
+            bound_reached: false,
+            violation: None,
+        };
+
+        let mut initial = State { components: self.components.clone(), pending: Vec::new() };
+        let init = catch_unwind(AssertUnwindSafe(|| {
+            for c in initial.components.iter_mut() {
+                c.init();
+            }
+        }));
+        let init = init.map_err(panic_message).and_then(|_| self.collect(&mut initial.pending));
+
+        if let Some(violation) = self.check(&initial, init) {
+            result.violation = Some(Counterexample { invariant: violation.0, message: violation.1, steps: vec![] });
+            return result;
+        }
+
+        // visited states with their parent, used to rebuild the counterexample,
+        // indexed by fingerprint
+        let mut visited: Vec<Visited<C, M, E>> = Vec::new();
+        let mut index: HashMap<u64, Vec<usize>> = HashMap::new();
+        index.insert(initial.fingerprint(self.order), vec![0]);
+        visited.push(Visited { state: initial, parent: None });
+
+        let mut frontier = VecDeque::new();
+        frontier.push_back((0, 0));
+
+        while let Some((current, depth)) = frontier.pop_front() {
+            result.max_depth = std::cmp::max(result.max_depth, depth);
+            let enabled = visited[current].state.enabled(self.order);
+
+            if depth >= self.max_depth {
+                result.bound_reached |= !enabled.is_empty();
+                continue;
+            }
+
+            for idx in enabled {
+                let transition = visited[current].state.pending[idx].transition();
+                let (next, executed) = self.execute(&visited[current].state, idx);
+                result.transitions += 1;
+
+                let same_hash = index.entry(next.fingerprint(self.order)).or_default();
+                if same_hash.iter().any(|other| visited[*other].state.equivalent(&next, self.order)) {
+                    continue;
+                }
+                let next_idx = visited.len();
+                same_hash.push(next_idx);
+                result.states += 1;
+
+                let violation = self.check(&next, executed);
+                visited.push(Visited { state: next, parent: Some((current, transition)) });
+
+                if let Some((invariant, message)) = violation {
+                    let steps = counterexample(&visited, next_idx);
+                    result.violation = Some(Counterexample { invariant, message, steps });
+                    return result;
+                }
+
+                if result.states >= self.max_states {
+                    result.bound_reached = true;
+                    return result;
+                }
+
+                frontier.push_back((next_idx, depth + 1));
+            }
+        }
+
+        result
+    }
+
+    /// executes pending item `idx` on a copy of the state
+    fn execute(&self, state: &State<C, M, E>, idx: usize) -> (State<C, M, E>, Result<(), String>) {
+        let mut next = state.clone();
+        let item = next.pending.remove(idx);
+
+        let executed = catch_unwind(AssertUnwindSafe(|| {
+            match item {
+                Pending::Message { channel, receiver, msg } => {
+                    next.components[receiver.as_idx()].receive_msg(channel, Box::new(msg));
+                }
+                Pending::Event { sender, receiver, event } => {
+                    next.components[receiver.as_idx()].process_event(sender, Box::new(event));
+                }
+            }
+        }));
+
+        let executed = executed.map_err(panic_message).and_then(|_| self.collect(&mut next.pending));
+        (next, executed)
+    }
+
+    /// moves events scheduled by components into pending items
+    fn collect(&self, pending: &mut Vec<Pending<M, E>>) -> Result<(), String> {
+        let failed = sim_sched().sim_status().is_err();
+        sim_sched().clear_error();
+
+        for event in sim_sched().take_events() {
+            match event {
+                EventType::MsgSendEvent(ev) if ev.channel.is_direct() => {
+                    let direct = *ev.message.downcast::<DirectMessage>().unwrap();
+                    pending.push(Pending::Message { channel: ev.channel, receiver: direct.receiver, msg: downcast(direct.message) });
+                }
+                EventType::MsgSendEvent(ev) => {
+                    let (left, right) = self.channels[ev.channel.as_idx()];
+                    let receiver = if ev.sender == left { right } else { left };
+                    pending.push(Pending::Message { channel: ev.channel, receiver, msg: downcast(ev.message) });
+                }
+                EventType::MsgRcvEvent(ev) => {
+                    pending.push(Pending::Message { channel: ev.channel, receiver: ev.receiver, msg: downcast(ev.message) });
+                }
+                EventType::ProcessEvent(ev) => {
+                    pending.push(Pending::Event { sender: ev.sender, receiver: ev.receiver, event: downcast(ev.event) });
+                }
+                EventType::EndSimulation => unreachable!(),
+            }
+        }
+
+        if failed {
+            return Err(String::from("component stopped the simulation with an error"));
+        }
+
+        Ok(())
+    }
+
+    /// returns name of the violated invariant and error message
+    fn check(&self, state: &State<C, M, E>, executed: Result<(), String>) -> Option<(String, Option<String>)> {
+        if let Err(message) = executed {
+            return Some((String::from("panic"), Some(message)));
+        }
+
+        self.invariants.iter()
+            .find(|(_, check)| !check(&state.components))
+            .map(|(name, _)| (name.clone(), None))
+    }
+}
+
+fn downcast<T: 'static>(payload: Box<dyn Any>) -> T {
+    *payload.downcast::<T>()
+        .unwrap_or_else(|_| panic!("explorer expects messages and events of type {}", std::any::type_name::<T>()))
+}
+
+pub(crate) fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        String::from(*msg)
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg.clone()
+    } else {
+        String::from("unknown panic")
+    }
+}
+
+struct Visited<C, M, E> {
+    state: State<C, M, E>,
+    /// index of the parent state and the transition leading from it to this state
+    parent: Option<(usize, Transition)>,
+}
+
+fn counterexample<C, M, E>(visited: &[Visited<C, M, E>], mut idx: usize) -> Vec<Transition> {
+    let mut steps = Vec::new();
+
+    while let Some((parent, transition)) = &visited[idx].parent {
+        steps.push(transition.clone());
+        idx = *parent;
+    }
+
+    steps.reverse();
+    steps
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Clone, Hash, PartialEq, Eq)]
+    struct Node {
+        id: ComponentId,
+        channels: Vec<ChannelId>,
+        send: bool,
+        received: Vec<u32>,
+    }
+
+    impl Component for Node {
+        fn sim_id(&self) -> ComponentId { self.id }
+
+        fn add_channel(&mut self, channel_id: ChannelId, _label: ChannelLabel) {
+            self.channels.push(channel_id);
+        }
+
+        fn init(&mut self) {
+            if self.send {
+                for ch in &self.channels {
+                    sim_sched().send_msg(self.id, *ch, Box::new(self.id.as_idx() as u32));
+                }
+            }
+        }
+
+        fn process_event(&mut self, _sender: ComponentId, _event: Box<dyn Any>) {}
+
+        fn receive_msg(&mut self, _channel: ChannelId, msg: Box<dyn Any>) {
+            self.received.push(*msg.downcast::<u32>().unwrap());
+        }
+
+        fn terminate(&mut self) {}
+    }
+
+    fn node(id: ComponentId, send: bool) -> Node {
+        Node { id, channels: vec![], send, received: vec![] }
+    }
+
+    #[test]
+    fn test_finds_shortest_counterexample() {
+        let mut explorer: Explorer<Node, u32> = Explorer::new();
+        let sink = explorer.add_component(|id| node(id, false));
+        let a = explorer.add_component(|id| node(id, true));
+        let b = explorer.add_component(|id| node(id, true));
+        explorer.add_channel(a, sink);
+        explorer.add_channel(b, sink);
+
+        let result = explorer.explore();
+        assert!(result.violation.is_none());
+        assert_eq!(result.states, 5);
+        assert_eq!(result.max_depth, 2);
+
+        explorer.add_invariant("a first", move |c| c[sink.as_idx()].received.first().is_none_or(|v| *v == 1));
+        let violation = explorer.explore().violation.unwrap();
+        assert_eq!(violation.invariant, "a first");
+        assert_eq!(violation.steps.len(), 1);
+        assert!(matches!(&violation.steps[0], Transition::Deliver { msg, .. } if msg == "2"));
+    }
+
+    #[test]
+    fn test_equivalent_states() {
+        let (a, b) = (ComponentId::new(0), ComponentId::new(1));
+        let msg = |msg: u32| Pending::<u32, ()>::Message { channel: ChannelId::new(0), receiver: b, msg };
+        let event = Pending::Event { sender: a, receiver: a, event: () };
+        let state = |pending| State { components: vec![node(a, false), node(b, false)], pending };
+
+        let sent = state(vec![msg(1), event.clone(), msg(2)]);
+        let reordered = state(vec![event, msg(2), msg(1)]);
+
+        // messages of a fifo channel are delivered in order, the event can happen at any time
+        assert!(!sent.equivalent(&reordered, ChannelOrder::Fifo));
+        assert!(sent.equivalent(&reordered, ChannelOrder::Unordered));
+        assert!(!sent.equivalent(&state(vec![msg(1), msg(2)]), ChannelOrder::Unordered));
+        assert!(!sent.equivalent(&state(vec![msg(1), msg(1), msg(2)]), ChannelOrder::Unordered));
+    }
+}
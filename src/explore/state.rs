@@ -0,0 +1,133 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+
+use crate::explore::explorer::ChannelOrder;
+use crate::keys::{ChannelId, ComponentId};
+
+/// message or event that has been scheduled but not yet executed
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub(super) enum Pending<M, E> {
+    Message { channel: ChannelId, receiver: ComponentId, msg: M },
+    Event { sender: ComponentId, receiver: ComponentId, event: E },
+}
+
+impl<M: Debug, E: Debug> Pending<M, E> {
+    pub fn transition(&self) -> Transition {
+        match self {
+            Pending::Message { channel, receiver, msg } => {
+                Transition::Deliver { channel: *channel, receiver: *receiver, msg: format!("{:?}", msg) }
+            }
+            Pending::Event { sender, receiver, event } => {
+                Transition::Event { sender: *sender, receiver: *receiver, event: format!("{:?}", event) }
+            }
+        }
+    }
+}
+
+/// step of an explored execution
+#[derive(Debug, Clone)]
+pub enum Transition {
+    /// message delivered to the receiver
+    Deliver { channel: ChannelId, receiver: ComponentId, msg: String },
+    /// event (e.g. timer) executed by the receiver
+    Event { sender: ComponentId, receiver: ComponentId, event: String },
+}
+
+/// global state: components and all pending messages and events
+#[derive(Clone)]
+pub(super) struct State<C, M, E> {
+    pub components: Vec<C>,
+    pub pending: Vec<Pending<M, E>>,
+}
+
+impl<C, M, E> State<C, M, E> {
+    /// position of every pending item in its fifo channel direction,
+    /// 0 for events and for messages of unordered channels
+    fn positions(&self, order: ChannelOrder) -> Vec<usize> {
+        let mut queue_len: HashMap<(ChannelId, ComponentId), usize> = HashMap::new();
+
+        self.pending.iter()
+            .map(|item| match (order, item) {
+                (ChannelOrder::Fifo, Pending::Message { channel, receiver, .. }) => {
+                    let pos = queue_len.entry((*channel, *receiver)).or_insert(0);
+                    *pos += 1;
+                    *pos - 1
+                }
+                _ => 0,
+            })
+            .collect()
+    }
+}
+
+impl<C, M, E> State<C, M, E>
+    where C: Hash, M: Hash, E: Hash
+{
+    /// hash of the state, pending items are treated as a multiset,
+    /// except for the order of messages within a fifo channel
+    pub fn fingerprint(&self, order: ChannelOrder) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.components.hash(&mut hasher);
+
+        let mut items: Vec<u64> = self.pending.iter()
+            .zip(self.positions(order))
+            .map(|(item, pos)| {
+                let mut h = DefaultHasher::new();
+                item.hash(&mut h);
+                pos.hash(&mut h);
+                h.finish()
+            })
+            .collect();
+        items.sort_unstable();
+        items.hash(&mut hasher);
+
+        hasher.finish()
+    }
+}
+
+impl<C, M, E> State<C, M, E>
+    where C: Eq, M: Eq, E: Eq
+{
+    /// same state in the sense of `fingerprint`: equal components and the same pending items
+    pub fn equivalent(&self, other: &Self, order: ChannelOrder) -> bool {
+        if self.components != other.components || self.pending.len() != other.pending.len() {
+            return false;
+        }
+
+        let other_positions = other.positions(order);
+        let mut matched = vec![false; other.pending.len()];
+
+        self.pending.iter().zip(self.positions(order)).all(|(item, pos)| {
+            let found = (0..other.pending.len())
+                .find(|idx| !matched[*idx] && other_positions[*idx] == pos && other.pending[*idx] == *item);
+            found.map(|idx| matched[idx] = true).is_some()
+        })
+    }
+
+    /// indices of pending items that can be executed next
+    pub fn enabled(&self, order: ChannelOrder) -> Vec<usize> {
+        match order {
+            ChannelOrder::Unordered => (0..self.pending.len()).collect(),
+            ChannelOrder::Fifo => {
+                // only the oldest message of each channel direction can be delivered
+                let mut heads: Vec<(ChannelId, ComponentId)> = Vec::new();
+                let mut enabled = Vec::new();
+
+                for (idx, item) in self.pending.iter().enumerate() {
+                    match item {
+                        Pending::Message { channel, receiver, .. } => {
+                            if !heads.contains(&(*channel, *receiver)) {
+                                heads.push((*channel, *receiver));
+                                enabled.push(idx);
+                            }
+                        }
+                        Pending::Event { .. } => enabled.push(idx),
+                    }
+                }
+
+                enabled
+            }
+        }
+    }
+}
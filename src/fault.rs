@@ -0,0 +1,47 @@
+use crate::keys::ComponentId;
+use crate::simtime::SimTime;
+use crate::simvars::sim_sched;
+
+/// event that crashes its receiver
+#[derive(Debug)]
+pub(crate) struct Crash;
+
+/// crash-stop failures, a crashed component does not execute any more events,
+/// messages to it are lost and its timers do not expire
+#[derive(Debug, Default)]
+pub(crate) struct FaultInjector {
+    crashes: Vec<(SimTime, ComponentId)>,
+    crashed: Vec<Option<SimTime>>,
+}
+
+impl FaultInjector {
+    pub fn crash_at(&mut self, time: SimTime, component: ComponentId) {
+        self.crashes.push((time, component));
+    }
+
+    /// schedules the crashes, called after the scheduler is initialized
+    pub fn init(&mut self, num_components: usize) {
+        self.crashed = vec![None; num_components];
+
+        for (time, component) in self.crashes.iter() {
+            let delay = *time - SimTime::default();
+            sim_sched().sched_component_event(delay, *component, *component, Box::new(Crash));
+        }
+    }
+
+    pub fn crash(&mut self, component: ComponentId) {
+        self.crashed[component.as_idx()].get_or_insert(*sim_sched().get_curr_time());
+    }
+
+    pub fn is_crashed(&self, component: ComponentId) -> bool {
+        self.crashed.get(component.as_idx()).is_some_and(|c| c.is_some())
+    }
+
+    /// crashed components and the time they crashed
+    pub fn crashed(&self) -> Vec<(ComponentId, SimTime)> {
+        self.crashed.iter()
+            .enumerate()
+            .filter_map(|(idx, time)| time.map(|time| (ComponentId::new(idx), time)))
+            .collect()
+    }
+}
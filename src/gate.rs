@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::keys::{ChannelId, ComponentId};
+
+/// name of a gate, `index` selects an element of a gate vector
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GateName {
+    pub name: String,
+    pub index: Option<usize>,
+}
+
+impl GateName {
+    /// parses `out` and `port[2]`, `port[]` is the next unconnected element of a gate vector
+    pub fn parse(gate: &str) -> Self {
+        match gate.strip_suffix(']').and_then(|g| g.split_once('[')) {
+            Some((name, "")) => GateName { name: String::from(name), index: None },
+            Some((name, index)) => {
+                let index = index.parse().unwrap_or_else(|_| panic!("invalid gate index in {}", gate));
+                GateName { name: String::from(name), index: Some(index) }
+            }
+            None => GateName { name: String::from(gate), index: None },
+        }
+    }
+}
+
+impl fmt::Display for GateName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.index {
+            Some(index) => write!(f, "{}[{}]", self.name, index),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+/// gate of a component, one end of a channel added with `Simulation::connect`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gate {
+    pub component: ComponentId,
+    pub name: GateName,
+}
+
+impl ComponentId {
+    /// gate of the component, e.g. `node.gate("out")` or `router.gate("port[1]")`
+    pub fn gate(&self, gate: &str) -> Gate {
+        Gate { component: *self, name: GateName::parse(gate) }
+    }
+
+    /// element `index` of a gate vector
+    pub fn gate_at(&self, name: &str, index: usize) -> Gate {
+        Gate { component: *self, name: GateName { name: String::from(name), index: Some(index) } }
+    }
+}
+
+/// gates declared by a component and the channels connected to them
+#[derive(Debug, Default)]
+pub struct Gates {
+    /// `None` for a single gate, `Some(size)` for a gate vector
+    declared: HashMap<String, Option<usize>>,
+    channels: HashMap<GateName, ChannelId>,
+    gates: HashMap<ChannelId, GateName>,
+}
+
+impl Gates {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn declare(&mut self, name: &str) -> &mut Self {
+        self.declared.insert(String::from(name), None);
+        self
+    }
+
+    pub fn declare_vector(&mut self, name: &str, size: usize) -> &mut Self {
+        self.declared.insert(String::from(name), Some(size));
+        self
+    }
+
+    /// channel connected to the gate, accepts the same names as `GateName::parse`
+    pub fn channel(&self, gate: &str) -> Option<ChannelId> {
+        self.channels.get(&GateName::parse(gate)).copied()
+    }
+
+    /// channel connected to element `index` of a gate vector
+    pub fn channel_at(&self, name: &str, index: usize) -> Option<ChannelId> {
+        self.channels.get(&GateName { name: String::from(name), index: Some(index) }).copied()
+    }
+
+    /// gate the channel is connected to
+    pub fn gate_of(&self, channel: ChannelId) -> Option<&GateName> {
+        self.gates.get(&channel)
+    }
+
+    /// declared size of a gate vector, 1 for a single gate
+    pub fn size(&self, name: &str) -> usize {
+        match self.declared.get(name) {
+            Some(size) => size.unwrap_or(1),
+            None => 0,
+        }
+    }
+
+    /// channels connected to the elements of a gate vector in index order
+    pub fn vector(&self, name: &str) -> impl Iterator<Item=ChannelId> + '_ {
+        let name = String::from(name);
+        (0..self.size(&name)).filter_map(move |index| self.channel_at(&name, index))
+    }
+
+    /// connects the channel, returns the gate with the index of a vector element
+    pub(crate) fn connect(&mut self, gate: &GateName, channel: ChannelId) -> Result<GateName, String> {
+        let gate = match (self.declared.get(&gate.name), gate.index) {
+            (None, _) => return Err(format!("gate {} is not declared", gate)),
+            (Some(None), None) => gate.clone(),
+            (Some(None), Some(_)) => return Err(format!("gate {} is not a gate vector", gate.name)),
+            (Some(Some(size)), Some(index)) if index >= *size => {
+                return Err(format!("gate {} out of range, size is {}", gate, size));
+            }
+            (Some(Some(_)), Some(_)) => gate.clone(),
+            (Some(Some(size)), None) => {
+                (0..*size)
+                    .map(|index| GateName { name: gate.name.clone(), index: Some(index) })
+                    .find(|g| !self.channels.contains_key(g))
+                    .ok_or_else(|| format!("all {} elements of gate vector {} are connected", size, gate.name))?
+            }
+        };
+
+        if self.channels.contains_key(&gate) {
+            return Err(format!("gate {} is already connected", gate));
+        }
+
+        self.channels.insert(gate.clone(), channel);
+        self.gates.insert(channel, gate.clone());
+        Ok(gate)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_connect_gate_vector() {
+        let mut gates = Gates::new();
+        gates.declare("out").declare_vector("port", 2);
+
+        assert_eq!(gates.connect(&GateName::parse("port[1]"), ChannelId::new(4)).unwrap().to_string(), "port[1]");
+        assert_eq!(gates.connect(&GateName::parse("port[]"), ChannelId::new(5)).unwrap().to_string(), "port[0]");
+        assert!(gates.connect(&GateName::parse("port[]"), ChannelId::new(6)).is_err());
+        assert!(gates.connect(&GateName::parse("out[0]"), ChannelId::new(6)).is_err());
+        assert!(gates.connect(&GateName::parse("in"), ChannelId::new(6)).is_err());
+
+        assert_eq!(gates.vector("port").collect::<Vec<_>>(), [ChannelId::new(5), ChannelId::new(4)]);
+        assert_eq!(gates.gate_of(ChannelId::new(4)), Some(&GateName::parse("port[1]")));
+        assert_eq!(gates.channel("out"), None);
+    }
+}
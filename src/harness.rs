@@ -0,0 +1,218 @@
+use std::any::Any;
+
+use crate::component::{ChannelLabel, Component, ComponentBuilder, downcast_mut, downcast_ref};
+use crate::environment::Environment;
+use crate::keys::{ChannelId, ComponentId};
+use crate::scheduler::{DirectMessage, EventType};
+use crate::simtime::{SimTime, SimTimeDelta};
+use crate::simvars::{init_scheduler, sim_env, sim_sched, sim_time};
+
+/// message sent by the component under test
+#[derive(Debug)]
+pub struct SentMessage {
+    /// time the message leaves the component
+    pub time: SimTime,
+    pub channel: ChannelId,
+    /// receiver of messages sent with `send_direct`
+    pub receiver: Option<ComponentId>,
+    pub message: Box<dyn Any>,
+}
+
+impl SentMessage {
+    pub fn message<T: 'static>(&self) -> Option<&T> {
+        self.message.downcast_ref::<T>()
+    }
+}
+
+/// event the component scheduled, e.g. a timer
+#[derive(Debug)]
+pub struct ScheduledEvent {
+    pub time: SimTime,
+    pub sender: ComponentId,
+    pub receiver: ComponentId,
+    pub event: Box<dyn Any>,
+}
+
+/// drives a single component without a simulation
+///
+/// the harness calls `init`, `receive_msg` and `process_event` directly and collects what the
+/// component sends and schedules. time only moves with `advance` and `advance_to`, which
+/// execute the events the component scheduled for itself, e.g. its timers
+pub struct ComponentHarness {
+    component: Box<dyn Component>,
+    num_channels: usize,
+    sent: Vec<SentMessage>,
+    scheduled: Vec<ScheduledEvent>,
+}
+
+impl ComponentHarness {
+    /// builds the component with id 0, starts a new scheduler for the current thread,
+    /// do not create a harness while a simulation on this thread is in use
+    pub fn new(builder: &mut dyn ComponentBuilder) -> Self {
+        init_scheduler();
+
+        ComponentHarness {
+            component: builder.build_component(ComponentId::new(0)),
+            num_channels: 0,
+            sent: Vec::new(),
+            scheduled: Vec::new(),
+        }
+    }
+
+    pub fn id(&self) -> ComponentId {
+        self.component.sim_id()
+    }
+
+    /// connects a channel to the component, channels are numbered from 0
+    pub fn add_channel(&mut self, label: ChannelLabel) -> ChannelId {
+        let channel_id = ChannelId::new(self.num_channels);
+        self.num_channels += 1;
+        self.component.add_channel(channel_id, label);
+        channel_id
+    }
+
+    pub fn env_mut(&mut self) -> &mut Environment {
+        sim_env()
+    }
+
+    pub fn time(&self) -> SimTime {
+        sim_time()
+    }
+
+    pub fn init(&mut self) -> &mut Self {
+        self.component.init();
+        self.collect();
+        self
+    }
+
+    pub fn receive<M: 'static>(&mut self, channel: ChannelId, msg: M) -> &mut Self {
+        self.component.receive_msg(channel, Box::new(msg));
+        self.collect();
+        self
+    }
+
+    pub fn event(&mut self, sender: ComponentId, event: Box<dyn Any>) -> &mut Self {
+        self.component.process_event(sender, event);
+        self.collect();
+        self
+    }
+
+    /// moves time forward by `delta`, see `advance_to`
+    pub fn advance(&mut self, delta: SimTimeDelta) -> &mut Self {
+        self.advance_to(sim_time() + delta)
+    }
+
+    /// executes the self events scheduled up to `time` in order and moves time to `time`,
+    /// events for other components stay in `scheduled`
+    pub fn advance_to(&mut self, time: SimTime) -> &mut Self {
+        let id = self.id();
+
+        while let Some(idx) = self.next_due(id, time) {
+            let ev = self.scheduled.remove(idx);
+            sim_sched().advance_time(ev.time);
+            self.component.process_event(ev.sender, ev.event);
+            self.collect();
+        }
+
+        sim_sched().advance_time(time);
+        self
+    }
+
+    /// messages sent so far, in order
+    pub fn sent(&self) -> &[SentMessage] {
+        &self.sent
+    }
+
+    pub fn take_sent(&mut self) -> Vec<SentMessage> {
+        std::mem::take(&mut self.sent)
+    }
+
+    /// messages of type `T` sent on the channel
+    pub fn sent_on<T: 'static>(&self, channel: ChannelId) -> Vec<&T> {
+        self.sent.iter().filter(|m| m.channel == channel).filter_map(|m| m.message::<T>()).collect()
+    }
+
+    /// events that were scheduled and not executed yet, in order of time
+    pub fn scheduled(&self) -> &[ScheduledEvent] {
+        &self.scheduled
+    }
+
+    pub fn component(&self) -> &dyn Component {
+        self.component.as_ref()
+    }
+
+    /// component under test or the process wrapped by it
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        downcast_ref(self.component.as_ref())
+    }
+
+    pub fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        downcast_mut(self.component.as_mut())
+    }
+
+    fn next_due(&self, id: ComponentId, time: SimTime) -> Option<usize> {
+        self.scheduled.iter().position(|ev| ev.receiver == id && ev.time <= time)
+    }
+
+    /// moves events from the scheduler to `sent` and `scheduled`
+    fn collect(&mut self) {
+        for (time, event) in sim_sched().take_scheduled() {
+            match event {
+                EventType::MsgSendEvent(ev) if ev.channel.is_direct() => {
+                    let direct = *ev.message.downcast::<DirectMessage>().unwrap();
+                    let time = time + direct.delay;
+                    self.sent.push(SentMessage { time, channel: ev.channel, receiver: Some(direct.receiver), message: direct.message });
+                }
+                EventType::MsgSendEvent(ev) => {
+                    self.sent.push(SentMessage { time, channel: ev.channel, receiver: None, message: ev.message });
+                }
+                EventType::MsgRcvEvent(ev) => {
+                    self.sent.push(SentMessage { time, channel: ev.channel, receiver: Some(ev.receiver), message: ev.message });
+                }
+                EventType::ProcessEvent(ev) => {
+                    self.scheduled.push(ScheduledEvent { time, sender: ev.sender, receiver: ev.receiver, event: ev.event });
+                }
+                EventType::EndSimulation => {}
+            }
+        }
+
+        // stable, events scheduled for the same time keep their order
+        self.scheduled.sort_by_key(|ev| ev.time);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algorithms::leader_election::hs::{HsAsynchProcess, HsBuilder, HsMessage, ProcessModel};
+    use crate::simtime::NO_DELTA;
+    use crate::util::uid::UniqueId;
+
+    #[test]
+    fn test_hs_process_in_harness() {
+        let mut builder = HsBuilder::with_uids(vec![UniqueId(5)]);
+        builder.model(ProcessModel::Asynch);
+        let mut harness = ComponentHarness::new(&mut builder);
+        let left = harness.add_channel(ChannelLabel::Left);
+        let right = harness.add_channel(ChannelLabel::Right);
+
+        // the start event is scheduled by init
+        harness.init();
+        assert_eq!(harness.scheduled().len(), 1);
+        harness.advance(NO_DELTA);
+        let probe = HsMessage::Out { uid: UniqueId(5), phase: 0, hops: 1 };
+        assert_eq!(harness.sent_on::<HsMessage>(left), [&probe]);
+        assert_eq!(harness.sent_on::<HsMessage>(right), [&probe]);
+
+        harness.take_sent();
+        harness.receive(left, HsMessage::Out { uid: UniqueId(9), phase: 0, hops: 1 });
+        assert_eq!(harness.sent_on::<HsMessage>(left), [&HsMessage::In { uid: UniqueId(9), phase: 0 }]);
+
+        harness.take_sent();
+        harness.receive(left, HsMessage::In { uid: UniqueId(5), phase: 0 })
+            .receive(right, HsMessage::In { uid: UniqueId(5), phase: 0 });
+        assert_eq!(harness.sent().len(), 2);
+        assert_eq!(harness.get::<HsAsynchProcess>().unwrap().hs().phase(), 1);
+        assert!(harness.get_mut::<HsAsynchProcess>().is_some());
+    }
+}
@@ -0,0 +1,199 @@
+use crate::component::Component;
+use crate::scheduler::EventInfo;
+use crate::simtime::SimTime;
+
+/// when an invariant is checked during the run
+#[derive(Debug, Clone)]
+pub enum CheckSchedule {
+    /// after every event
+    EveryEvent,
+    /// after every n-th event
+    EveryNEvents(usize),
+    /// once at each of the times, after the last event up to that time and before the first
+    /// event after it, times after the end of the run are checked on the final state.
+    /// times between the same two events are checked once
+    AtTimes(Vec<SimTime>),
+}
+
+pub type InvariantFn<ChannelT> = Box<dyn Fn(&[Box<dyn Component>], &[ChannelT]) -> bool>;
+
+pub struct Invariant<ChannelT> {
+    pub name: String,
+    schedule: CheckSchedule,
+    check: InvariantFn<ChannelT>,
+    /// index of the next time to check for `CheckSchedule::AtTimes`
+    next_time: usize,
+}
+
+impl<ChannelT> Invariant<ChannelT> {
+    pub fn new(name: &str, schedule: CheckSchedule, check: InvariantFn<ChannelT>) -> Self {
+        let schedule = match schedule {
+            CheckSchedule::EveryNEvents(0) => panic!("invariant {} checked every 0 events", name),
+            CheckSchedule::AtTimes(mut times) => {
+                times.sort();
+                CheckSchedule::AtTimes(times)
+            }
+            schedule => schedule,
+        };
+
+        Invariant { name: String::from(name), schedule, check, next_time: 0 }
+    }
+
+    /// checks the invariant if it is due
+    ///
+    /// # Arguments
+    ///
+    /// * `num_events`: number of events executed so far
+    /// * `next_event`: time of the next event on the queue, `None` at the end of the run
+    ///
+    /// returns false if the invariant was checked and does not hold
+    pub(crate) fn check_if_due(&mut self,
+                               num_events: usize,
+                               next_event: Option<SimTime>,
+                               components: &[Box<dyn Component>],
+                               channels: &[ChannelT],
+    ) -> bool {
+        let due = match &self.schedule {
+            CheckSchedule::EveryEvent => true,
+            CheckSchedule::EveryNEvents(n) => num_events.is_multiple_of(*n),
+            CheckSchedule::AtTimes(times) => {
+                let mut due = false;
+                // state at time t is final once the next event is later than t
+                while self.next_time < times.len()
+                    && next_event.is_none_or(|next| next > times[self.next_time])
+                {
+                    self.next_time += 1;
+                    due = true;
+                }
+                due
+            }
+        };
+
+        !due || (self.check)(components, channels)
+    }
+
+    /// checks the times of `CheckSchedule::AtTimes` that were not reached when the run ended
+    pub(crate) fn check_at_end(&mut self, components: &[Box<dyn Component>], channels: &[ChannelT]) -> bool {
+        match &self.schedule {
+            CheckSchedule::AtTimes(_) => self.check_if_due(0, None, components, channels),
+            _ => true,
+        }
+    }
+}
+
+/// invariant that did not hold during the run
+#[derive(Debug, Clone)]
+pub struct InvariantViolation {
+    pub name: String,
+    pub time: SimTime,
+    /// last event executed before the check
+    pub event: EventInfo,
+}
+
+#[cfg(test)]
+mod test {
+    use std::any::Any;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::channels::delay_channel::DelayChannel;
+    use crate::component::{ChannelLabel, ComponentBuilder, downcast_ref};
+    use crate::keys::{ChannelId, ComponentId};
+    use crate::sim::Simulation;
+    use crate::simtime::SimTimeDelta;
+    use crate::simvars::sim_sched;
+
+    /// ticks every 10ms and terminates after the fourth tick with the next tick scheduled
+    struct Ticker {
+        id: ComponentId,
+        ticks: u32,
+    }
+
+    impl Ticker {
+        fn tick_later(&self) {
+            sim_sched().sched_self_event(SimTimeDelta::from(Duration::from_millis(10)), self.id);
+        }
+    }
+
+    impl Component for Ticker {
+        fn sim_id(&self) -> ComponentId { self.id }
+
+        fn add_channel(&mut self, _channel_id: ChannelId, _label: ChannelLabel) {}
+
+        fn init(&mut self) {
+            self.tick_later();
+        }
+
+        fn process_event(&mut self, _sender: ComponentId, _event: Box<dyn Any>) {
+            self.ticks += 1;
+            self.tick_later();
+        }
+
+        fn receive_msg(&mut self, _incoming_channel: ChannelId, _msg: Box<dyn Any>) {}
+
+        fn terminate(&mut self) {}
+
+        fn is_terminated(&self) -> bool {
+            self.ticks >= 4
+        }
+    }
+
+    struct TickerBuilder;
+
+    impl ComponentBuilder for TickerBuilder {
+        fn build_component(&mut self, id: ComponentId) -> Box<dyn Component> {
+            Box::new(Ticker { id, ticks: 0 })
+        }
+    }
+
+    fn ticks(components: &[Box<dyn Component>]) -> u32 {
+        downcast_ref::<Ticker>(components[0].as_ref()).unwrap().ticks
+    }
+
+    fn ms(millis: u64) -> SimTime {
+        SimTime::from(Duration::from_millis(millis))
+    }
+
+    /// ticks seen by an invariant with the schedule, the run ends at 40ms
+    fn checked_ticks(schedule: CheckSchedule) -> Vec<u32> {
+        let checked = Rc::new(RefCell::new(Vec::new()));
+        let mut simulation = Simulation::<DelayChannel>::default();
+        simulation.add_component(&mut TickerBuilder);
+        let log = checked.clone();
+        simulation.add_invariant("log", schedule, move |components, _| {
+            log.borrow_mut().push(ticks(components));
+            true
+        });
+
+        simulation.call_init();
+        simulation.run().unwrap();
+        checked.take()
+    }
+
+    #[test]
+    fn test_check_schedules() {
+        assert_eq!(checked_ticks(CheckSchedule::EveryEvent), [1, 2, 3, 4]);
+        assert_eq!(checked_ticks(CheckSchedule::EveryNEvents(2)), [2, 4]);
+        // 15ms sees the state before the tick at 20ms, 20ms and 25ms share one check
+        // and 100ms is after the end of the run
+        assert_eq!(checked_ticks(CheckSchedule::AtTimes(vec![ms(100), ms(25), ms(15), ms(20)])), [1, 2, 4]);
+    }
+
+    #[test]
+    fn test_violation() {
+        let mut simulation = Simulation::<DelayChannel>::default();
+        let ticker = simulation.add_component(&mut TickerBuilder);
+        simulation.add_invariant("at most one tick", CheckSchedule::AtTimes(vec![ms(15), ms(25)]),
+                                 |components, _| ticks(components) <= 1);
+
+        simulation.call_init();
+        assert!(simulation.run().is_err());
+
+        let violation = simulation.violation().unwrap();
+        assert_eq!(violation.name, "at most one tick");
+        assert_eq!(violation.time, ms(20));
+        assert_eq!(violation.event, EventInfo::ProcessEvent { sender: ticker, receiver: ticker });
+    }
+}
@@ -4,6 +4,9 @@ use crate::channel::Channel;
 
 // ChannelId ---------------------------------------------------
 
+/// incoming channel of messages sent with `Scheduler::send_direct`
+pub const DIRECT_CHANNEL: ChannelId = ChannelId { id: usize::MAX - 1, _marker: PhantomData };
+
 #[derive(PartialEq, Eq, Copy, Clone, Hash)]
 pub struct ChannelId {
     id: usize,
@@ -35,11 +38,18 @@ impl ChannelId {
     pub fn is_initialized (&self) -> bool {
         self.id != std::usize::MAX
     }
+
+    /// true for messages delivered without a channel
+    pub fn is_direct(&self) -> bool {
+        *self == DIRECT_CHANNEL
+    }
 }
 
 impl std::fmt::Debug for ChannelId {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        if self.id != std::usize::MAX {
+        if self.is_direct() {
+            write!(f, "(direct)")
+        } else if self.id != std::usize::MAX {
             write!(f, "({})", self.id)
         } else {
             write!(f, "(uninitialized)")
@@ -51,7 +61,7 @@ impl std::fmt::Debug for ChannelId {
 
 pub const DUMMY_COMPONENT: ComponentId = ComponentId { id: usize::MAX, _marker: PhantomData };
 
-#[derive(PartialEq, Eq, Copy, Clone)]
+#[derive(PartialEq, Eq, Copy, Clone, Hash)]
 pub struct ComponentId {
     id: usize,
     _marker: PhantomData<dyn Component>
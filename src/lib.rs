@@ -1,9 +1,11 @@
 pub use crate::sim::Simulation;
 pub use crate::channels::delay_channel::*;
+pub use crate::channels::partial_synch_channel::*;
+pub use crate::drift::{ClockDriftModel, DriftingClock};
 pub use crate::component::*;
 pub use crate::keys::*;
 pub use crate::simtime::*;
-pub use crate::simvars::{sim_sched, sim_env, sim_time};
+pub use crate::simvars::{sim_sched, sim_env, sim_time, sim_clocks, sim_name, sim_local_clock};
 
 
 pub mod environment;
@@ -20,11 +22,29 @@ mod channels;
 pub mod simvars;
 
 pub mod synch;
+pub mod asynch;
 
 pub mod topo;
 
 pub mod basicnet;
+pub mod experiment;
+pub mod clocks;
+pub mod trace;
+pub mod invariant;
+pub mod termination;
+pub mod explore;
+pub mod stats;
+pub mod algorithms;
+pub mod snapshot;
+pub mod compound;
+pub mod gate;
+pub mod names;
+pub mod timer;
+pub mod harness;
+pub mod testing;
+mod fault;
 pub mod log;
+pub mod drift;
 
 
 
@@ -0,0 +1,27 @@
+use std::fmt;
+
+use crate::keys::ComponentId;
+use crate::simvars::{sim_name, sim_time};
+
+/// prefix of log lines of the component, simulation time and name of the component
+pub fn prefix(component: ComponentId) -> String {
+    format!("[{}ms] {}:", sim_time().as_millis(), sim_name(component))
+}
+
+pub fn log(component: ComponentId, args: fmt::Arguments) {
+    println!("{} {}", prefix(component), args);
+}
+
+/// prints a line of the simulation itself prefixed with the simulation time
+pub fn log_sim(args: fmt::Arguments) {
+    println!("[{}ms] {}", sim_time().as_millis(), args);
+}
+
+/// prints a line prefixed with the simulation time and the name of the component,
+/// e.g. `sim_log!(self.id(), "received {:?}", msg)`
+#[macro_export]
+macro_rules! sim_log {
+    ($component:expr, $($arg:tt)*) => {
+        $crate::log::log($component, format_args!($($arg)*))
+    };
+}
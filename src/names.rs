@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+
+use crate::keys::ComponentId;
+
+/// names and type names of the components of a simulation
+///
+/// components are named `c<idx>` unless the builder or the scenario names them, `c<idx>_<n>`
+/// if that name is taken. submodules of compounds are named by their path, e.g. `net.router[3].l3`
+#[derive(Debug, Default, Clone)]
+pub struct ComponentNames {
+    names: Vec<String>,
+    type_names: Vec<String>,
+    index: HashMap<String, ComponentId>,
+}
+
+impl ComponentNames {
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// name of the component, `c<idx>` for components unknown to the simulation
+    pub fn name(&self, component: ComponentId) -> String {
+        match self.names.get(component.as_idx()) {
+            Some(name) => name.clone(),
+            None => format!("c{}", component.as_idx()),
+        }
+    }
+
+    pub fn type_name(&self, component: ComponentId) -> Option<&str> {
+        self.type_names.get(component.as_idx()).map(|t| t.as_str())
+    }
+
+    /// component by its name or path
+    pub fn lookup(&self, path: &str) -> Option<ComponentId> {
+        self.index.get(path).copied()
+    }
+
+    /// components whose path starts with `prefix.`, e.g. all submodules of a compound
+    pub fn children<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item=ComponentId> + 'a {
+        self.names.iter()
+            .enumerate()
+            .filter(move |(_, name)| name.strip_prefix(prefix).is_some_and(|rest| rest.starts_with('.')))
+            .map(|(idx, _)| ComponentId::new(idx))
+    }
+
+    pub(crate) fn push(&mut self, name: Option<String>, type_name: String) {
+        let id = ComponentId::new(self.names.len());
+        let name = name.unwrap_or_else(|| self.default_name(id));
+        self.check_unique(&name, id.as_idx());
+        self.index.insert(name.clone(), id);
+        self.names.push(name);
+        self.type_names.push(type_name);
+    }
+
+    pub(crate) fn set_name(&mut self, component: ComponentId, name: String) {
+        self.check_unique(&name, component.as_idx());
+        self.index.remove(&self.names[component.as_idx()]);
+        self.index.insert(name.clone(), component);
+        self.names[component.as_idx()] = name;
+    }
+
+    /// `c<idx>`, or `c<idx>_<n>` with the smallest n that is not taken
+    fn default_name(&self, component: ComponentId) -> String {
+        let name = format!("c{}", component.as_idx());
+        if !self.index.contains_key(&name) {
+            return name;
+        }
+
+        (1..).map(|n| format!("{}_{}", name, n))
+            .find(|name| !self.index.contains_key(name))
+            .unwrap()
+    }
+
+    fn check_unique(&self, name: &str, idx: usize) {
+        if let Some(other) = self.lookup(name).filter(|other| other.as_idx() != idx) {
+            panic!("component name {} is already used by {:?}", name, other);
+        }
+    }
+}
+
+/// type name without module paths, e.g. `AsynchComponent<Router>`
+pub(crate) fn short_type_name(type_name: &str) -> String {
+    let mut short = String::new();
+    let mut segment_start = 0;
+
+    for c in type_name.chars() {
+        if c == ':' {
+            short.truncate(segment_start);
+        } else {
+            short.push(c);
+            if !(c.is_alphanumeric() || c == '_') {
+                segment_start = short.len();
+            }
+        }
+    }
+
+    short
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_lookup_by_path() {
+        let mut names = ComponentNames::default();
+        names.push(None, short_type_name("d2simrs::asynch::process::AsynchComponent<simpledv::router::Router>"));
+        names.push(Some(String::from("net.router[3].l3")), String::from("L3"));
+        names.push(Some(String::from("net.router[3]x")), String::from("L3"));
+
+        assert_eq!(names.name(ComponentId::new(0)), "c0");
+        assert_eq!(names.type_name(ComponentId::new(0)), Some("AsynchComponent<Router>"));
+        assert_eq!(names.lookup("net.router[3].l3"), Some(ComponentId::new(1)));
+        assert_eq!(names.children("net.router[3]").collect::<Vec<_>>(), [ComponentId::new(1)]);
+        assert_eq!(names.lookup("net.router[4].l3"), None);
+    }
+
+    #[test]
+    fn test_default_names_do_not_collide() {
+        let mut names = ComponentNames::default();
+        names.push(Some(String::from("c1")), String::from("A"));
+        names.push(None, String::from("A"));
+        names.push(Some(String::from("c2_1")), String::from("A"));
+        names.set_name(ComponentId::new(2), String::from("c2"));
+        names.push(None, String::from("A"));
+
+        let all: Vec<String> = (0..names.len()).map(|idx| names.name(ComponentId::new(idx))).collect();
+        assert_eq!(all, ["c1", "c1_1", "c2", "c3"]);
+        assert_eq!(names.lookup("c1_1"), Some(ComponentId::new(1)));
+        assert_eq!(names.lookup("c2_1"), None);
+        assert_eq!(names.lookup("c2"), Some(ComponentId::new(2)));
+    }
+}
@@ -1,11 +1,27 @@
 use std::any::Any;
 use std::cmp::Ordering;
 use std::cmp::PartialEq;
-use std::collections::BinaryHeap;
+use std::collections::VecDeque;
 
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use crate::clocks::{ClockStamp, LogicalClocks};
+use crate::drift::DriftingClock;
 use crate::environment::Environment;
-use crate::keys::{ChannelId, ComponentId};
+use crate::keys::{ChannelId, ComponentId, DIRECT_CHANNEL};
+use crate::names::ComponentNames;
 use crate::simtime::{NO_DELTA, SimTime, SimTimeDelta};
+use crate::snapshot::Marker;
+use crate::trace::{Envelope, MsgId};
+
+pub use self::calendar::CalendarQueue;
+pub use self::fel::{BinaryHeapList, EventListKind, FutureEventList};
+pub use self::ladder::LadderQueue;
+
+mod calendar;
+mod fel;
+mod ladder;
 
 #[derive(Debug)]
 pub struct ComponentEvent {
@@ -28,6 +44,14 @@ pub struct MessageRcvEvent {
     pub message: Box<dyn Any>,
 }
 
+/// payload of a send event on `DIRECT_CHANNEL`
+#[derive(Debug)]
+pub(crate) struct DirectMessage {
+    pub receiver: ComponentId,
+    pub delay: SimTimeDelta,
+    pub message: Box<dyn Any>,
+}
+
 #[derive(Debug)]
 pub enum EventType {
     ProcessEvent(ComponentEvent),
@@ -36,14 +60,64 @@ pub enum EventType {
     EndSimulation,
 }
 
+/// summary of an event that does not hold its payload
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum EventInfo {
+    ProcessEvent { sender: ComponentId, receiver: ComponentId },
+    MsgSendEvent { sender: ComponentId, channel: ChannelId },
+    MsgRcvEvent { channel: ChannelId, receiver: ComponentId },
+}
+
+impl EventType {
+    /// sending or receiving end of a channel the message event uses, direct messages have none
+    fn channel_direction(&self) -> Option<(bool, ComponentId, ChannelId)> {
+        match self {
+            EventType::MsgSendEvent(ev) if !ev.channel.is_direct() => Some((true, ev.sender, ev.channel)),
+            EventType::MsgRcvEvent(ev) if !ev.channel.is_direct() => Some((false, ev.receiver, ev.channel)),
+            _ => None,
+        }
+    }
+
+    pub fn info(&self) -> Option<EventInfo> {
+        match self {
+            EventType::ProcessEvent(ev) => {
+                Some(EventInfo::ProcessEvent { sender: ev.sender, receiver: ev.receiver })
+            }
+            EventType::MsgSendEvent(ev) => {
+                Some(EventInfo::MsgSendEvent { sender: ev.sender, channel: ev.channel })
+            }
+            EventType::MsgRcvEvent(ev) => {
+                Some(EventInfo::MsgRcvEvent { channel: ev.channel, receiver: ev.receiver })
+            }
+            EventType::EndSimulation => None,
+        }
+    }
+}
+
+/// event on the future event list, `index` orders events scheduled for the same time
 #[derive(Debug)]
-struct ScheduledEvent
+pub struct ScheduledEvent
 {
     time: SimTime,
     index: usize,
     event: EventType,
 }
 
+impl ScheduledEvent {
+    pub fn time(&self) -> SimTime {
+        self.time
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// events leave the future event list in order of their keys
+    pub fn key(&self) -> (SimTime, usize) {
+        (self.time, self.index)
+    }
+}
+
 impl Ord for ScheduledEvent {
     fn cmp(&self, other: &Self) -> Ordering
     {
@@ -70,35 +144,159 @@ impl PartialEq for ScheduledEvent {
 
 impl Eq for ScheduledEvent {}
 
+/// how events scheduled for the same time are ordered
+#[derive(Debug, Clone, Default)]
+pub enum TieBreak {
+    /// in the order they were scheduled
+    #[default]
+    Fifo,
+    /// in random order, the same seed gives the same order. messages on the same
+    /// channel direction keep the order they were sent in
+    Random { seed: u64 },
+    /// in the order given by recorded decisions (see `ScheduleDecision::chosen`),
+    /// ties left after the decisions are used up are ordered fifo
+    Replay(Vec<usize>),
+}
+
+impl TieBreak {
+    /// replays the decisions recorded in a trace
+    pub fn replay(decisions: &[ScheduleDecision]) -> Self {
+        TieBreak::Replay(decisions.iter().map(|d| d.chosen).collect())
+    }
+}
+
+/// choice of the next event among several events scheduled for the same time
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ScheduleDecision {
+    pub time: SimTime,
+    /// number of events scheduled for the time that can be executed next, a message can not
+    /// overtake an earlier message on the same channel direction
+    pub candidates: usize,
+    /// position of the executed event among the candidates, in the order they were scheduled
+    pub chosen: usize,
+    pub event: EventInfo,
+}
+
+enum TieBreaker {
+    Fifo,
+    Random(Box<StdRng>),
+    Replay(VecDeque<usize>),
+}
+
+impl From<TieBreak> for TieBreaker {
+    fn from(tie_break: TieBreak) -> Self {
+        match tie_break {
+            TieBreak::Fifo => TieBreaker::Fifo,
+            TieBreak::Random { seed } => TieBreaker::Random(Box::new(StdRng::seed_from_u64(seed))),
+            TieBreak::Replay(decisions) => TieBreaker::Replay(decisions.into()),
+        }
+    }
+}
+
+impl TieBreaker {
+    fn choose(&mut self, candidates: usize) -> usize {
+        match self {
+            TieBreaker::Fifo => 0,
+            TieBreaker::Random(rng) => rng.gen_range(0..candidates),
+            TieBreaker::Replay(decisions) => {
+                decisions.pop_front().filter(|chosen| *chosen < candidates).unwrap_or(0)
+            }
+        }
+    }
+}
+
 pub enum SimStatus {
     Ok,  Failure
 }
 
+/// message stamped by `send_msg`, the simulation moves it into the trace
+#[derive(Debug)]
+pub(crate) struct SentMessage {
+    pub time: SimTime,
+    pub sender: ComponentId,
+    pub channel: ChannelId,
+    pub msg_id: MsgId,
+    pub stamp: Option<ClockStamp>,
+}
+
 pub struct Scheduler
 {
-    events: BinaryHeap<ScheduledEvent>,
+    events: Box<dyn FutureEventList>,
     curr_time: SimTime,
     pub(crate) env: Environment,
+    pub(crate) clocks: Option<LogicalClocks>,
+    pub(crate) names: ComponentNames,
+    /// local clocks of components, empty unless clock drift is enabled
+    pub(crate) local_clocks: Vec<DriftingClock>,
+    /// wrap sent messages in an `Envelope`, set if tracing or logical clocks are enabled
+    pub(crate) stamp_messages: bool,
+    pub(crate) sent: Vec<SentMessage>,
+    next_msg_id: MsgId,
     sim_status: SimStatus,
     next_event: usize,
+    tie_break: TieBreaker,
+    decision: Option<ScheduleDecision>,
 }
 
 impl Scheduler
 {
     pub fn new() -> Self {
         Scheduler {
-            events: BinaryHeap::default(),
+            events: EventListKind::default().build(),
             curr_time: SimTime::default(),
             env: Environment::default(),
+            clocks: None,
+            names: ComponentNames::default(),
+            local_clocks: Vec::new(),
+            stamp_messages: false,
+            sent: Vec::new(),
+            next_msg_id: 0,
             sim_status: SimStatus::Ok,
             next_event: 0,
+            tie_break: TieBreaker::Fifo,
+            decision: None,
         }
     }
 
+    pub fn set_tie_break(&mut self, tie_break: TieBreak) {
+        self.tie_break = tie_break.into();
+    }
+
+    /// replaces the future event list, pending events move to the new list
+    pub fn set_event_list(&mut self, kind: EventListKind) {
+        let mut events = kind.build();
+        while let Some(ev) = self.events.pop() {
+            events.push(ev);
+        }
+        self.events = events;
+    }
+
+    /// decision taken by the last `next_event()`, if there was a tie
+    pub fn take_decision(&mut self) -> Option<ScheduleDecision> {
+        self.decision.take()
+    }
+
     pub fn get_curr_time(&self) -> &SimTime {
         return &self.curr_time;
     }
 
+    /// number of messages sent by components on channels but not yet received,
+    /// snapshot markers and direct messages are not counted
+    pub fn messages_in_flight(&self) -> usize {
+        self.events.iter()
+            .filter(|ev| match &ev.event {
+                EventType::MsgSendEvent(msg) => !msg.channel.is_direct() && !msg.message.is::<Marker>(),
+                EventType::MsgRcvEvent(msg) => !msg.channel.is_direct() && !msg.message.is::<Marker>(),
+                _ => false,
+            })
+            .count()
+    }
+
+    /// time of the next event on the queue
+    pub fn next_event_time(&self) -> Option<SimTime> {
+        self.events.peek().map(|ev| ev.time)
+    }
+
     pub fn next_event(&mut self) -> EventType {
 
         if let SimStatus::Failure = self.sim_status {
@@ -111,7 +309,11 @@ impl Scheduler
             return EventType::EndSimulation;
         }
 
-        let event = event.unwrap();
+        let mut event = event.unwrap();
+
+        if !matches!(self.tie_break, TieBreaker::Fifo) {
+            event = self.break_tie(event);
+        }
 
         // updaate time
         self.curr_time.advance_to(event.time);
@@ -126,7 +328,17 @@ impl Scheduler
         }
     }
 
+    /// the message is sent now, it leaves the sender after `timedelta`
     pub fn send_msg_delayed(&mut self, timedelta: SimTimeDelta, sender: ComponentId, channel: ChannelId, message: Box<dyn Any>) {
+        let message = match channel.is_direct() {
+            true => message,
+            false => self.stamp(sender, channel, message),
+        };
+        self.send_unstamped(timedelta, sender, channel, message);
+    }
+
+    /// sends a message of the simulation itself, e.g. a snapshot marker, that is not traced
+    pub(crate) fn send_unstamped(&mut self, timedelta: SimTimeDelta, sender: ComponentId, channel: ChannelId, message: Box<dyn Any>) {
         let time = self.curr_time + timedelta;
         let event = ScheduledEvent {
             time,
@@ -142,6 +354,29 @@ impl Scheduler
         self.send_msg_delayed(NO_DELTA, sender, channel, message);
     }
 
+    /// delivers the message to `receiver` after `timedelta` without a channel, e.g. from an
+    /// oracle or a shared medium. the receiver gets it in `receive_msg` on `DIRECT_CHANNEL`
+    pub fn send_direct(&mut self, sender: ComponentId, receiver: ComponentId, timedelta: SimTimeDelta, message: Box<dyn Any>) {
+        let message = self.stamp(sender, DIRECT_CHANNEL, message);
+        let message = Box::new(DirectMessage { receiver, delay: timedelta, message });
+        self.send_msg(sender, DIRECT_CHANNEL, message);
+    }
+
+    /// ticks the clock of the sender and wraps the message if messages are stamped,
+    /// at the time the sender sends it and not when the send event is executed
+    fn stamp(&mut self, sender: ComponentId, channel: ChannelId, message: Box<dyn Any>) -> Box<dyn Any> {
+        if !self.stamp_messages {
+            return message;
+        }
+
+        let msg_id = self.next_msg_id;
+        self.next_msg_id += 1;
+
+        let stamp = self.clocks.as_mut().map(|c| c.tick(sender));
+        self.sent.push(SentMessage { time: self.curr_time, sender, channel, msg_id, stamp: stamp.clone() });
+        Box::new(Envelope::new(msg_id, stamp, message))
+    }
+
     pub fn sched_receive_msg(&mut self, timedelta: SimTimeDelta, receiver: ComponentId, channel: ChannelId, message: Box<dyn Any>) {
         let time = self.curr_time + timedelta;
         let event = ScheduledEvent {
@@ -182,6 +417,69 @@ impl Scheduler
         self.sim_status = SimStatus::Failure;
     }
 
+    /// removes all events from the queue in execution order, without advancing time
+    pub(crate) fn take_events(&mut self) -> Vec<EventType> {
+        self.take_scheduled().into_iter().map(|(_, event)| event).collect()
+    }
+
+    /// like `take_events`, with the time each event was scheduled for
+    pub(crate) fn take_scheduled(&mut self) -> Vec<(SimTime, EventType)> {
+        let mut events = Vec::with_capacity(self.events.len());
+        while let Some(ev) = self.events.pop() {
+            events.push((ev.time, ev.event));
+        }
+        events
+    }
+
+    /// moves the current time forward without executing events
+    pub(crate) fn advance_time(&mut self, time: SimTime) {
+        self.curr_time.advance_to(time);
+    }
+
+    pub(crate) fn clear_error(&mut self) {
+        self.sim_status = SimStatus::Ok;
+    }
+
+    /// picks one of the events scheduled for the same time as `first`
+    fn break_tie(&mut self, first: ScheduledEvent) -> ScheduledEvent {
+        let mut ties = vec![first];
+        while self.events.peek().is_some_and(|ev| ev.time == ties[0].time) {
+            ties.push(self.events.pop().unwrap());
+        }
+
+        // messages on the same channel direction keep the order they were sent in,
+        // so fifo channels stay fifo
+        let mut heads = Vec::new();
+        let eligible: Vec<usize> = (0..ties.len())
+            .filter(|idx| match ties[*idx].event.channel_direction() {
+                Some(direction) if heads.contains(&direction) => false,
+                Some(direction) => {
+                    heads.push(direction);
+                    true
+                }
+                None => true,
+            })
+            .collect();
+
+        if eligible.len() == 1 {
+            let event = ties.remove(eligible[0]);
+            for ev in ties {
+                self.events.push(ev);
+            }
+            return event;
+        }
+
+        let candidates = eligible.len();
+        let chosen = self.tie_break.choose(candidates);
+        let event = ties.remove(eligible[chosen]);
+        for ev in ties {
+            self.events.push(ev);
+        }
+
+        self.decision = Some(ScheduleDecision { time: event.time, candidates, chosen, event: event.event.info().unwrap() });
+        event
+    }
+
     fn push_event(&mut self, event: ScheduledEvent) {
         self.events.push(event);
         self.next_event += 1;
@@ -194,7 +492,7 @@ mod test {
 
     #[test]
     fn test_order1() {
-        const NUM_EV: usize = 5; // 3 does not work
+        const NUM_EV: usize = 5;
 
         let time = SimTime::default();
         let process = ComponentId::new(1);
@@ -226,6 +524,78 @@ mod test {
         assert!(matches!(sched.next_event(), EventType::EndSimulation));
     }
 
+    #[test]
+    fn test_random_tie_break_replay() {
+        const NUM_EV: usize = 8;
+
+        let time = SimTime::default();
+        let process = ComponentId::new(1);
+
+        let run = |tie_break: TieBreak| {
+            let mut sched = Scheduler::new();
+            sched.set_tie_break(tie_break);
+            for idx in 0..NUM_EV {
+                sched.events.push(crete_event(time, process, idx, idx));
+            }
+
+            let mut order = Vec::new();
+            let mut decisions = Vec::new();
+            for _ in 0..NUM_EV {
+                order.push(unwrap_process_event(sched.next_event()));
+                decisions.extend(sched.take_decision());
+            }
+            (order, decisions)
+        };
+
+        let (order, decisions) = run(TieBreak::Random { seed: 7 });
+        assert_eq!(decisions.len(), NUM_EV - 1);
+        assert_ne!(order, (0..NUM_EV).collect::<Vec<_>>());
+        assert_eq!(run(TieBreak::Random { seed: 7 }).0, order);
+        assert_eq!(run(TieBreak::replay(&decisions)).0, order);
+    }
+
+    #[test]
+    fn test_event_lists_keep_order() {
+        let process = ComponentId::new(1);
+
+        let run = |kind: EventListKind| {
+            let mut rng = StdRng::seed_from_u64(3);
+            let mut events = kind.build();
+            let mut now = 0;
+            let mut order = Vec::new();
+
+            // bursts at the same time, close and far apart events, popped in between after
+            // a first batch that arrives in random order
+            for idx in 0..5000 {
+                let time = now + match rng.gen_range(0..3) {
+                    0 => 0,
+                    1 => rng.gen_range(0..10),
+                    _ => rng.gen_range(0..100_000),
+                };
+                events.push(crete_event(SimTime::from(std::time::Duration::from_nanos(time)), process, idx, idx));
+
+                if idx >= 1000 && rng.gen_bool(0.4) {
+                    let key = events.peek().unwrap().key();
+                    let ev = events.pop().unwrap();
+                    assert_eq!(ev.key(), key);
+                    now = ev.time.time().as_nanos() as u64;
+                    order.push(key);
+                }
+            }
+            assert_eq!(events.iter().count(), events.len());
+            while let Some(ev) = events.pop() {
+                order.push(ev.key());
+            }
+            order
+        };
+
+        let order = run(EventListKind::BinaryHeap);
+        assert_eq!(order.len(), 5000);
+        assert!(order.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(run(EventListKind::Calendar), order);
+        assert_eq!(run(EventListKind::Ladder), order);
+    }
+
     fn unwrap_process_event(popped_ev: EventType) -> usize {
         let ev = match popped_ev {
             EventType::ProcessEvent(ev) => { Some(ev) },
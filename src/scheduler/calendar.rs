@@ -0,0 +1,120 @@
+use std::collections::VecDeque;
+
+use crate::scheduler::ScheduledEvent;
+use crate::scheduler::fel::{FutureEventList, nanos};
+
+const MIN_BUCKETS: usize = 2;
+/// events sampled to estimate the bucket width on resize
+const SAMPLE: usize = 25;
+
+/// calendar queue (R. Brown, 1988)
+///
+/// a day is a bucket of `width` nanoseconds, a year are all buckets. an event goes to
+/// bucket `(time / width) % buckets`, buckets are sorted. the queue grows and shrinks with
+/// the number of events and then estimates the width from the gaps between the next events
+#[derive(Debug)]
+pub struct CalendarQueue {
+    buckets: Vec<VecDeque<ScheduledEvent>>,
+    width: u64,
+    /// day of the last event taken, no pending event is earlier
+    day: u64,
+    len: usize,
+}
+
+impl Default for CalendarQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CalendarQueue {
+    pub fn new() -> Self {
+        CalendarQueue {
+            buckets: (0..MIN_BUCKETS).map(|_| VecDeque::new()).collect(),
+            width: 1,
+            day: 0,
+            len: 0,
+        }
+    }
+
+    fn insert(&mut self, event: ScheduledEvent) {
+        let idx = (nanos(event.time) / self.width) as usize % self.buckets.len();
+        let bucket = &mut self.buckets[idx];
+        let pos = bucket.partition_point(|ev| ev.key() < event.key());
+        bucket.insert(pos, event);
+    }
+
+    /// bucket of the earliest event, searches one year from the current day and
+    /// falls back to the earliest head of all buckets
+    fn find(&self) -> Option<usize> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let num_buckets = self.buckets.len() as u64;
+        for day in self.day..self.day + num_buckets {
+            let idx = (day % num_buckets) as usize;
+            if self.buckets[idx].front().is_some_and(|ev| nanos(ev.time) / self.width == day) {
+                return Some(idx);
+            }
+        }
+
+        (0..self.buckets.len())
+            .filter(|idx| !self.buckets[*idx].is_empty())
+            .min_by_key(|idx| self.buckets[*idx][0].key())
+    }
+
+    fn resize(&mut self, num_buckets: usize) {
+        let mut events: Vec<ScheduledEvent> = self.buckets.iter_mut().flat_map(|b| b.drain(..)).collect();
+        events.sort_by_key(|ev| ev.key());
+
+        // 3 times the average gap between the next events
+        let sample: Vec<u64> = events.iter().take(SAMPLE).map(|ev| nanos(ev.time)).collect();
+        if let (Some(first), Some(last)) = (sample.first(), sample.last()) {
+            let gap = (last - first) / sample.len() as u64;
+            self.width = (3 * gap).max(1);
+            self.day = first / self.width;
+        }
+
+        self.buckets = (0..num_buckets).map(|_| VecDeque::new()).collect();
+        for event in events {
+            self.insert(event);
+        }
+    }
+}
+
+impl FutureEventList for CalendarQueue {
+    fn push(&mut self, event: ScheduledEvent) {
+        self.day = self.day.min(nanos(event.time) / self.width);
+        self.insert(event);
+        self.len += 1;
+
+        if self.len > 2 * self.buckets.len() {
+            self.resize(2 * self.buckets.len());
+        }
+    }
+
+    fn pop(&mut self) -> Option<ScheduledEvent> {
+        let idx = self.find()?;
+        let event = self.buckets[idx].pop_front().unwrap();
+        self.day = nanos(event.time) / self.width;
+        self.len -= 1;
+
+        if self.len < self.buckets.len() / 2 && self.buckets.len() > MIN_BUCKETS {
+            self.resize(self.buckets.len() / 2);
+        }
+        Some(event)
+    }
+
+    fn peek(&self) -> Option<&ScheduledEvent> {
+        self.find().and_then(|idx| self.buckets[idx].front())
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = &ScheduledEvent> + '_> {
+        Box::new(self.buckets.iter().flatten())
+    }
+}
@@ -0,0 +1,81 @@
+use std::collections::BinaryHeap;
+
+use crate::scheduler::ScheduledEvent;
+use crate::scheduler::calendar::CalendarQueue;
+use crate::scheduler::ladder::LadderQueue;
+use crate::simtime::SimTime;
+
+/// pending events of the scheduler
+///
+/// events leave the list in order of time, events scheduled for the same time
+/// in order of their index, i.e. in the order they were scheduled
+pub trait FutureEventList {
+    fn push(&mut self, event: ScheduledEvent);
+
+    fn pop(&mut self) -> Option<ScheduledEvent>;
+
+    fn peek(&self) -> Option<&ScheduledEvent>;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// all pending events, in any order
+    fn iter(&self) -> Box<dyn Iterator<Item = &ScheduledEvent> + '_>;
+}
+
+/// implementation of the future event list, see `Simulation::with_event_list`
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub enum EventListKind {
+    #[default]
+    BinaryHeap,
+    /// Brown's calendar queue, O(1) for evenly spread events
+    Calendar,
+    /// ladder queue of Tang et al., O(1) for most distributions of event times
+    Ladder,
+}
+
+impl EventListKind {
+    pub fn build(self) -> Box<dyn FutureEventList> {
+        match self {
+            EventListKind::BinaryHeap => Box::new(BinaryHeapList::default()),
+            EventListKind::Calendar => Box::new(CalendarQueue::new()),
+            EventListKind::Ladder => Box::new(LadderQueue::new()),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct BinaryHeapList {
+    // ordered by the reversed `Ord` of `ScheduledEvent`, the top is the earliest event
+    heap: BinaryHeap<ScheduledEvent>,
+}
+
+impl FutureEventList for BinaryHeapList {
+    fn push(&mut self, event: ScheduledEvent) {
+        self.heap.push(event);
+    }
+
+    fn pop(&mut self) -> Option<ScheduledEvent> {
+        self.heap.pop()
+    }
+
+    fn peek(&self) -> Option<&ScheduledEvent> {
+        self.heap.peek()
+    }
+
+    fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = &ScheduledEvent> + '_> {
+        Box::new(self.heap.iter())
+    }
+}
+
+/// time as integer for the bucket arithmetic of calendar and ladder queues
+pub(super) fn nanos(time: SimTime) -> u64 {
+    time.time().as_nanos() as u64
+}
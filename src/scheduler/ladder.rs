@@ -0,0 +1,159 @@
+use crate::scheduler::ScheduledEvent;
+use crate::scheduler::fel::{FutureEventList, nanos};
+
+/// buckets with more events are split into a new rung instead of being sorted,
+/// a bottom with more events is spread over a new rung
+const THRESHOLD: usize = 50;
+
+#[derive(Debug)]
+struct Rung {
+    start: u64,
+    width: u64,
+    buckets: Vec<Vec<ScheduledEvent>>,
+    /// buckets before `current` were moved to the bottom or to the next rung
+    current: usize,
+}
+
+impl Rung {
+    fn new(start: u64, width: u64, num_buckets: usize) -> Self {
+        Rung { start, width, buckets: (0..num_buckets).map(|_| Vec::new()).collect(), current: 0 }
+    }
+
+    fn current_start(&self) -> u64 {
+        self.start + self.current as u64 * self.width
+    }
+
+    fn insert(&mut self, event: ScheduledEvent) {
+        let idx = ((nanos(event.time) - self.start) / self.width) as usize;
+        let last = self.buckets.len() - 1;
+        self.buckets[idx.min(last)].push(event);
+    }
+}
+
+/// ladder queue (W. T. Tang, R. S. M. Goh, I. L.-J. Thng, 2005)
+///
+/// new events go unsorted to the top, the top is spread over a rung of buckets when the
+/// bottom runs empty. a large bucket is spread over a finer rung, a small one is sorted
+/// and becomes the bottom, from which events are taken. the bottom is refilled eagerly,
+/// so the earliest event is always its last element
+#[derive(Debug, Default)]
+pub struct LadderQueue {
+    top: Vec<ScheduledEvent>,
+    /// events at or after `top_start` go to the top
+    top_start: u64,
+    rungs: Vec<Rung>,
+    /// sorted, earliest event last
+    bottom: Vec<ScheduledEvent>,
+    len: usize,
+}
+
+impl LadderQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn refill(&mut self) {
+        while self.bottom.is_empty() {
+            if self.rungs.is_empty() {
+                if self.top.is_empty() {
+                    return;
+                }
+                self.spread_top();
+            }
+
+            let rung = self.rungs.last_mut().unwrap();
+            while rung.current < rung.buckets.len() && rung.buckets[rung.current].is_empty() {
+                rung.current += 1;
+            }
+            if rung.current == rung.buckets.len() {
+                self.rungs.pop();
+                continue;
+            }
+
+            let bucket_start = rung.current_start();
+            let width = rung.width;
+            let mut bucket = std::mem::take(&mut rung.buckets[rung.current]);
+            rung.current += 1;
+
+            if bucket.len() > THRESHOLD && width > 1 {
+                let num_buckets = bucket.len();
+                let mut child = Rung::new(bucket_start, width.div_ceil(num_buckets as u64), num_buckets);
+                for event in bucket {
+                    child.insert(event);
+                }
+                self.rungs.push(child);
+            } else {
+                bucket.sort_by_key(|ev| std::cmp::Reverse(ev.key()));
+                self.bottom = bucket;
+            }
+        }
+    }
+
+    fn spread_top(&mut self) {
+        let min = self.top.iter().map(|ev| nanos(ev.time)).min().unwrap();
+        let max = self.top.iter().map(|ev| nanos(ev.time)).max().unwrap();
+        let num_buckets = self.top.len();
+
+        let mut rung = Rung::new(min, (max - min) / num_buckets as u64 + 1, num_buckets);
+        for event in self.top.drain(..) {
+            rung.insert(event);
+        }
+        self.rungs.push(rung);
+        self.top_start = max + 1;
+    }
+
+    /// moves the bottom to a new rung that reaches up to the next rung or the top,
+    /// so that later events before that rung still find a bucket
+    fn spread_bottom(&mut self) {
+        let min = nanos(self.bottom[self.bottom.len() - 1].time);
+        let end = self.rungs.last().map_or(self.top_start, |r| r.current_start());
+        let num_buckets = self.bottom.len();
+
+        let mut rung = Rung::new(min, (end - min) / num_buckets as u64 + 1, num_buckets);
+        for event in self.bottom.drain(..) {
+            rung.insert(event);
+        }
+        self.rungs.push(rung);
+    }
+}
+
+impl FutureEventList for LadderQueue {
+    fn push(&mut self, event: ScheduledEvent) {
+        let time = nanos(event.time);
+        self.len += 1;
+
+        if time >= self.top_start {
+            self.top.push(event);
+        } else if let Some(rung) = self.rungs.iter_mut().find(|r| time >= r.current_start()) {
+            rung.insert(event);
+        } else {
+            let pos = self.bottom.partition_point(|ev| ev.key() > event.key());
+            self.bottom.insert(pos, event);
+
+            if self.bottom.len() > THRESHOLD && self.bottom[0].time != self.bottom[self.bottom.len() - 1].time {
+                self.spread_bottom();
+            }
+        }
+
+        self.refill();
+    }
+
+    fn pop(&mut self) -> Option<ScheduledEvent> {
+        let event = self.bottom.pop()?;
+        self.len -= 1;
+        self.refill();
+        Some(event)
+    }
+
+    fn peek(&self) -> Option<&ScheduledEvent> {
+        self.bottom.last()
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = &ScheduledEvent> + '_> {
+        Box::new(self.top.iter().chain(self.rungs.iter().flat_map(|r| r.buckets.iter().flatten())).chain(self.bottom.iter()))
+    }
+}
@@ -1,18 +1,58 @@
+use std::any::Any;
+
 use crate::channel::Channel;
 use crate::channel::ChannelBuilder;
-use crate::component::{ChannelLabel, Component, ComponentBuilder};
+use crate::component::{ChannelLabel, Component, ComponentBuilder, downcast_mut, downcast_ref};
+use crate::compound::{Compound, CompoundBuilder, Module};
+use crate::drift::ClockDriftModel;
+use crate::environment::Environment;
+use crate::fault::{Crash, FaultInjector};
+use crate::gate::Gate;
+use crate::invariant::{CheckSchedule, Invariant, InvariantViolation};
 use crate::keys::{ChannelId, ComponentId};
-use crate::scheduler::EventType;
-use crate::simtime::SimTime;
-use crate::simvars::{SIM, sim_sched};
+use crate::log::log_sim;
+use crate::names::ComponentNames;
+use crate::scheduler::{DirectMessage, EventInfo, EventListKind, EventType, TieBreak};
+use crate::simtime::{NO_DELTA, SimTime};
+use crate::simvars::{init_scheduler, sim_env, sim_sched};
+use crate::snapshot::{GlobalSnapshot, Marker, SnapshotCollector, SnapshotId, StartSnapshot};
+use crate::stats::{ComplexityReport, StatsCollector};
+use crate::termination::{TerminationDetector, TerminationReport};
+use crate::trace::{Envelope, Trace};
+use crate::trace::recorder::Recorder;
 
 pub type Components = Vec<Box<dyn Component>>;
 
+/// what a channel id refers to
+enum ChannelSlot {
+    /// index in `Simulation::channels`
+    Channel(usize),
+    /// zero-delay link between submodules of a compound
+    Link(ComponentId, ComponentId),
+}
+
 pub struct Simulation<ChannelT>
     where ChannelT: Channel
 {
     components: Components,
     channels: Vec<ChannelT>,
+    channel_slots: Vec<ChannelSlot>,
+    compounds: Vec<Compound>,
+    names: ComponentNames,
+    /// moved to the scheduler by `call_init`
+    env: Option<Environment>,
+    recorder: Recorder,
+    invariants: Vec<Invariant<ChannelT>>,
+    violation: Option<InvariantViolation>,
+    num_events: usize,
+    termination: TerminationDetector,
+    tie_break: TieBreak,
+    event_list: EventListKind,
+    /// maximal drift and seed of the local clocks
+    clock_drift: Option<(f64, u64)>,
+    stats: StatsCollector,
+    snapshots: SnapshotCollector,
+    faults: FaultInjector,
     // scheduler: Scheduler,
 }
 
@@ -21,6 +61,21 @@ impl<ChannelT: Channel> Default for Simulation<ChannelT> {
         Self {
             components: Vec::new(),
             channels: Vec::new(),
+            channel_slots: Vec::new(),
+            compounds: Vec::new(),
+            names: ComponentNames::default(),
+            env: Some(Environment::default()),
+            recorder: Recorder::default(),
+            invariants: Vec::new(),
+            violation: None,
+            num_events: 0,
+            termination: TerminationDetector::default(),
+            tie_break: TieBreak::default(),
+            event_list: EventListKind::default(),
+            clock_drift: None,
+            stats: StatsCollector::default(),
+            snapshots: SnapshotCollector::default(),
+            faults: FaultInjector::default(),
             // scheduler: Scheduler::new(),
         }
     }
@@ -28,20 +83,50 @@ impl<ChannelT: Channel> Default for Simulation<ChannelT> {
 
 impl<ChannelT: Channel> Simulation<ChannelT> {
 
+    /// simulation with another future event list than the binary heap,
+    /// the order of events does not depend on the list
+    pub fn with_event_list(event_list: EventListKind) -> Self {
+        Simulation { event_list, ..Self::default() }
+    }
+
     pub fn add_component(&mut self, builder: &mut dyn ComponentBuilder) -> ComponentId {
         let id = self.components.len();
         let id = ComponentId::new(id);
 
-        self.components.push(builder.build_component(id));
+        let component = builder.build_component(id);
+        self.names.push(builder.component_name(id), component.type_name());
+        self.components.push(component);
+        id
+    }
+
+    pub fn add_named_component(&mut self, name: &str, builder: &mut dyn ComponentBuilder) -> ComponentId {
+        let id = self.add_component(builder);
+        self.set_name(id, name);
         id
     }
 
+    /// renames the component, names must be unique
+    pub fn set_name(&mut self, component: ComponentId, name: &str) {
+        self.names.set_name(component, String::from(name));
+    }
+
+    pub fn names(&self) -> &ComponentNames {
+        &self.names
+    }
+
+    /// component by its name or path, e.g. `net.router[3].l3`
+    pub fn lookup(&self, path: &str) -> Option<ComponentId> {
+        self.names.lookup(path)
+    }
+
     pub fn add_channel<ChannelBuilderT>(&mut self, builder: &mut ChannelBuilderT, left: ComponentId, right: ComponentId) -> ChannelId
         where ChannelBuilderT: ChannelBuilder<C = ChannelT>
     {
-        let channel_id = self.channels.len();
+        let channel_id = self.channel_slots.len();
         let channel_id = ChannelId::new(channel_id);
+        self.channel_slots.push(ChannelSlot::Channel(self.channels.len()));
         self.channels.push(builder.build_channel(channel_id, left, right));
+        self.snapshots.add_channel(left, right);
         let p_left = &mut self.components[left.as_idx()];
         p_left.add_channel(channel_id, ChannelLabel::Left);
         let p_right = &mut self.components[right.as_idx()];
@@ -49,46 +134,344 @@ impl<ChannelT: Channel> Simulation<ChannelT> {
         channel_id
     }
 
+    /// adds a channel between two gates, e.g. `connect(a.gate("out"), b.gate("in[]"), builder)`,
+    /// `a` gets the channel with `ChannelLabel::Left`
+    pub fn connect<ChannelBuilderT>(&mut self, a: Gate, b: Gate, builder: &mut ChannelBuilderT) -> ChannelId
+        where ChannelBuilderT: ChannelBuilder<C = ChannelT>
+    {
+        let channel_id = self.add_channel(builder, a.component, b.component);
+        self.connect_gate(&a, channel_id);
+        self.connect_gate(&b, channel_id);
+        channel_id
+    }
+
+    fn connect_gate(&mut self, gate: &Gate, channel_id: ChannelId) {
+        let component = &mut self.components[gate.component.as_idx()];
+        let gates = component.gates_mut()
+            .unwrap_or_else(|| panic!("component {:?} has no gates", gate.component));
+
+        if let Err(err) = gates.connect(&gate.name, channel_id) {
+            panic!("cannot connect component {:?}: {}", gate.component, err);
+        }
+    }
+
+    /// adds the submodules of a compound component and the links between them,
+    /// channels to other components are connected to `Compound::gate`
+    pub fn add_compound(&mut self, name: &str, builder: &mut dyn CompoundBuilder) -> Compound {
+        let mut module = Module::new(self.components.len(), self.channel_slots.len());
+        builder.build_compound(&mut module);
+        let compound = module.compound(name);
+
+        for component in module.components.iter() {
+            self.names.push(None, component.type_name());
+        }
+        for (submodule, id) in compound.submodules() {
+            self.names.set_name(*id, format!("{}.{}", name, submodule));
+        }
+        self.components.append(&mut module.components);
+
+        for (left, right) in module.links {
+            let channel_id = ChannelId::new(self.channel_slots.len());
+            self.channel_slots.push(ChannelSlot::Link(left, right));
+            self.snapshots.add_channel(left, right);
+            self.components[left.as_idx()].add_channel(channel_id, ChannelLabel::Left);
+            self.components[right.as_idx()].add_channel(channel_id, ChannelLabel::Right);
+        }
+
+        for (channel_id, gate) in module.link_gates {
+            self.connect_gate(&gate, channel_id);
+        }
+
+        self.compounds.push(compound.clone());
+        compound
+    }
+
+    /// component or the process wrapped by it, e.g. to check the outcome of a run
+    pub fn component<T: 'static>(&self, id: ComponentId) -> Option<&T> {
+        self.components.get(id.as_idx()).and_then(|c| downcast_ref(c.as_ref()))
+    }
+
+    pub fn component_mut<T: 'static>(&mut self, id: ComponentId) -> Option<&mut T> {
+        self.components.get_mut(id.as_idx()).and_then(|c| downcast_mut(c.as_mut()))
+    }
+
+    /// all components of type `T` or wrapping a process of type `T`
+    pub fn components_of<T: 'static>(&self) -> impl Iterator<Item=(ComponentId, &T)> {
+        self.components.iter()
+            .enumerate()
+            .filter_map(|(idx, c)| downcast_ref(c.as_ref()).map(|c| (ComponentId::new(idx), c)))
+    }
+
+    pub fn compounds(&self) -> &[Compound] {
+        &self.compounds
+    }
+
+    /// values shared with components and invariants, read them with `sim_env()` during the run
+    pub fn env(&self) -> &Environment {
+        self.env.as_ref().unwrap_or_else(|| sim_env())
+    }
+
+    pub fn env_mut(&mut self) -> &mut Environment {
+        self.env.as_mut().unwrap_or_else(|| sim_env())
+    }
+
+    /// records every event executed by the simulation, see `trace()`
+    pub fn enable_tracing(&mut self) {
+        self.recorder.enable_trace();
+    }
+
+    /// maintains lamport and vector clocks for all components,
+    /// clocks are updated on every local event, send and receive
+    pub fn enable_logical_clocks(&mut self) {
+        self.recorder.enable_clocks();
+    }
+
+    /// gives every component a local clock with a rate in `[1 - max_drift, 1 + max_drift]`,
+    /// see `sim_local_clock`
+    pub fn enable_clock_drift(&mut self, max_drift: f64, seed: u64) {
+        assert!((0.0..1.0).contains(&max_drift), "drift has to be in [0, 1)");
+        self.clock_drift = Some((max_drift, seed));
+    }
+
+    /// order of events scheduled for the same time, fifo by default,
+    /// with tracing enabled the decisions are recorded in the trace
+    pub fn set_tie_break(&mut self, tie_break: TieBreak) {
+        self.tie_break = tie_break;
+    }
+
+    /// names messages of type `T` in the complexity report,
+    /// the size of a message is the size of the type in memory
+    pub fn register_message<T: Any>(&mut self, name: &str) {
+        self.register_message_with_bits::<T, _>(name, |_| 8 * std::mem::size_of::<T>());
+    }
+
+    /// names messages of type `T` in the complexity report, `bits` gives the size of a message
+    pub fn register_message_with_bits<T, F>(&mut self, name: &str, bits: F)
+        where T: Any, F: Fn(&T) -> usize + 'static
+    {
+        self.stats.register::<T>(name, Box::new(move |msg| bits(msg.downcast_ref::<T>().unwrap())));
+    }
+
+    /// messages and bits sent, by type, channel and component, and time of termination
+    pub fn complexity(&self) -> ComplexityReport {
+        let termination = self.termination_report();
+        let termination_time = termination.last_termination.filter(|_| termination.all_terminated);
+        self.stats.report(termination_time, termination.end_time)
+    }
+
+    /// takes a Chandy-Lamport snapshot started by `initiator` at `time`, call before `call_init`
+    ///
+    /// the simulation sends markers on behalf of the components over their channels,
+    /// so `call_init` rejects channels that are not fifo, see `Channel::is_fifo`.
+    /// snapshots that can not be completed are kept, see `incomplete_snapshots`. direct
+    /// messages are not recorded, the run fails if one is sent while a snapshot is collected
+    pub fn snapshot_at(&mut self, time: SimTime, initiator: ComponentId) -> SnapshotId {
+        self.snapshots.request(time, initiator)
+    }
+
+    /// crashes the component at `time`, call before `call_init`
+    ///
+    /// a crashed component stops executing events, messages sent to it are lost
+    /// and it never terminates
+    pub fn crash_at(&mut self, time: SimTime, component: ComponentId) {
+        self.faults.crash_at(time, component);
+    }
+
+    /// components that crashed so far and the time they crashed
+    pub fn crashed(&self) -> Vec<(ComponentId, SimTime)> {
+        self.faults.crashed()
+    }
+
+    /// copies messages of type `T` into the channel states of snapshots
+    pub fn register_snapshot_message<T: Any + Clone>(&mut self) {
+        self.snapshots.register::<T>();
+    }
+
+    /// completed snapshots, in order of completion
+    pub fn snapshots(&self) -> &[GlobalSnapshot] {
+        self.snapshots.snapshots()
+    }
+
+    /// snapshots that were started but not completed so far, e.g. because a component crashed
+    pub fn incomplete_snapshots(&self) -> Vec<&GlobalSnapshot> {
+        self.snapshots.incomplete()
+    }
+
+    pub fn trace(&self) -> Option<&Trace> {
+        self.recorder.trace()
+    }
+
     pub fn call_init(&mut self) {
         println!("\nInitializing simulation: #components {}", self.components.len());
 
-        unsafe {
-            SIM.init();
-        };
+        let env = self.env.take().unwrap_or_else(|| std::mem::take(sim_env()));
+
+        init_scheduler();
+
+        sim_sched().env = env;
+
+        sim_sched().set_event_list(self.event_list);
+        sim_sched().set_tie_break(self.tie_break.clone());
+        sim_sched().names = self.names.clone();
+        if let Some((max_drift, seed)) = self.clock_drift {
+            let mut model = ClockDriftModel::new(max_drift, seed);
+            sim_sched().local_clocks = (0..self.components.len()).map(|_| model.next_clock()).collect();
+        }
+        self.recorder.init(&self.names);
+        self.termination.init(self.components.len());
+        self.stats.init(self.components.len(), self.channel_slots.len());
+        if self.snapshots.is_requested() {
+            assert!(self.channels.iter().all(|c| c.is_fifo()), "snapshots need fifo channels");
+        }
+        self.snapshots.init();
+        self.faults.init(self.components.len());
 
         for p in self.components.iter_mut() {
             // debug(p);
             p.init();
+            self.termination.update(p.as_ref(), SimTime::default());
         }
+        self.recorder.on_sent();
+    }
+
+    /// registers an invariant over all components and channels (links of compounds are not
+    /// channels), the run stops with failure as soon as the invariant does not hold
+    pub fn add_invariant<F>(&mut self, name: &str, schedule: CheckSchedule, check: F)
+        where F: Fn(&[Box<dyn Component>], &[ChannelT]) -> bool + 'static
+    {
+        self.invariants.push(Invariant::new(name, schedule, Box::new(check)));
+    }
+
+    /// first invariant that did not hold during the run
+    pub fn violation(&self) -> Option<&InvariantViolation> {
+        self.violation.as_ref()
+    }
+
+    /// termination status of the components at the current time of the run
+    pub fn termination_report(&self) -> TerminationReport {
+        self.termination.report(sim_sched().messages_in_flight(), *sim_sched().get_curr_time())
     }
 
     pub fn step(&mut self) -> bool {
 
+        if self.termination.all_terminated() {
+            return false;
+        }
+
         let event = sim_sched().next_event();
+        if let Some(decision) = sim_sched().take_decision() {
+            self.recorder.on_decision(decision);
+        }
 
         // let event2 = sim_sched_mut().next_event();
         // eprintln!("event2 = {:?}", event2);
 
+        let info = event.info();
+
         match event {
+            EventType::ProcessEvent(ev_data) if ev_data.event.is::<Crash>() => {
+                self.faults.crash(ev_data.receiver);
+            },
+            EventType::ProcessEvent(ev_data) if ev_data.event.is::<StartSnapshot>() => {
+                let StartSnapshot(id) = *ev_data.event.downcast::<StartSnapshot>().unwrap();
+                match self.faults.is_crashed(ev_data.receiver) {
+                    true => self.snapshots.abort(id, ev_data.receiver, self.components.len()),
+                    false => self.snapshots.start(id, ev_data.receiver, &self.components),
+                }
+            },
+            // events of crashed components are dropped
+            EventType::ProcessEvent(ev_data) if self.faults.is_crashed(ev_data.receiver) => {},
+            EventType::MsgSendEvent(ev_data) if self.faults.is_crashed(ev_data.sender) => {},
+            EventType::MsgRcvEvent(ev_data) if self.faults.is_crashed(ev_data.receiver) => {},
             EventType::ProcessEvent(ev_data) => {
+                self.recorder.on_local(ev_data.receiver, ev_data.sender);
                 let component = &mut self.components[ev_data.receiver.as_idx()];
                 component.process_event(ev_data.sender, ev_data.event);
+                self.termination.update(component.as_ref(), *sim_sched().get_curr_time());
+            },
+            EventType::MsgSendEvent(ev_data) if ev_data.message.is::<Marker>() => {
+                self.forward(ev_data.sender, ev_data.channel, ev_data.message);
+            },
+            EventType::MsgSendEvent(ev_data) if ev_data.channel.is_direct() && self.snapshots.is_collecting() => {
+                // no marker separates direct messages sent before and after the snapshot
+                log_sim(format_args!("direct message from {:?} while a snapshot is collected", ev_data.sender));
+                sim_sched().sim_error();
+            },
+            EventType::MsgSendEvent(ev_data) if ev_data.channel.is_direct() => {
+                // direct messages do not use a channel, they are traced but not counted
+                let direct = *ev_data.message.downcast::<DirectMessage>().unwrap();
+                sim_sched().sched_receive_msg(direct.delay, direct.receiver, ev_data.channel, direct.message);
             },
             EventType::MsgSendEvent(ev_data) => {
-                let channel = &mut self.channels[ev_data.channel.as_idx()];
-                channel.accept_message_from(ev_data.sender, ev_data.message);
+                self.stats.on_send(ev_data.sender, ev_data.channel, Envelope::open(ev_data.message.as_ref()));
+                self.forward(ev_data.sender, ev_data.channel, ev_data.message);
+            },
+            EventType::MsgRcvEvent(ev_data) if ev_data.message.is::<Marker>() => {
+                let marker = *ev_data.message.downcast::<Marker>().unwrap();
+                self.snapshots.on_marker(marker, ev_data.channel, ev_data.receiver, &self.components);
             },
             EventType::MsgRcvEvent(ev_data) => {
                 // println!("event at time: {}", self.scheduler.curr_time);
+                let message = self.recorder.on_receive(ev_data.receiver, ev_data.channel, ev_data.message);
+                if !ev_data.channel.is_direct() {
+                    self.stats.on_receive(ev_data.receiver);
+                    self.snapshots.on_message(ev_data.channel, ev_data.receiver, message.as_ref());
+                }
                 let component = &mut self.components[ev_data.receiver.as_idx()];
-                component.receive_msg(ev_data.channel, ev_data.message);
+                component.receive_msg(ev_data.channel, message);
+                self.termination.update(component.as_ref(), *sim_sched().get_curr_time());
             }
             EventType::EndSimulation => {return false;}
         }
 
+        self.recorder.on_sent();
+        self.num_events += 1;
+        self.check_invariants(info.unwrap(), false);
+
+        if self.termination.all_terminated() {
+            if self.violation.is_none() {
+                self.check_invariants(info.unwrap(), true);
+            }
+            log_sim(format_args!("termination detected: {}", self.termination_report()));
+            return false;
+        }
+
         true
     }
 
+    /// passes the message to the channel or delivers it over the link
+    fn forward(&mut self, sender: ComponentId, channel_id: ChannelId, message: Box<dyn Any>) {
+        match self.channel_slots[channel_id.as_idx()] {
+            ChannelSlot::Channel(idx) => self.channels[idx].accept_message_from(sender, message),
+            ChannelSlot::Link(left, right) => {
+                let receiver = if sender == left { right } else { left };
+                sim_sched().sched_receive_msg(NO_DELTA, receiver, channel_id, message);
+            }
+        }
+    }
+
+    /// checks the invariants that are due after the event, at the end of the run the
+    /// remaining times of `CheckSchedule::AtTimes` are checked as well
+    fn check_invariants(&mut self, event: EventInfo, end_of_run: bool) {
+        let now = *sim_sched().get_curr_time();
+        let next_event = sim_sched().next_event_time();
+
+        for invariant in self.invariants.iter_mut() {
+            let holds = match end_of_run {
+                false => invariant.check_if_due(self.num_events, next_event, &self.components, &self.channels),
+                true => invariant.check_at_end(&self.components, &self.channels),
+            };
+            if holds {
+                continue;
+            }
+
+            log_sim(format_args!("invariant {} violated after event {:?}", invariant.name, event));
+            self.violation = Some(InvariantViolation { name: invariant.name.clone(), time: now, event });
+            sim_sched().sim_error();
+            break;
+        }
+    }
+
     pub fn run(&mut self) -> Result<(), ()> {
         // eprintln!("self.scheduler.events = {:?}", self.scheduler.events);
         println!("\nRunning simulation");
@@ -121,4 +504,4 @@ impl<ChannelT: Channel> Simulation<ChannelT> {
     pub fn validate(&self, validate: fn(&[Box<dyn Component>]) -> bool) {
         assert!(validate(&self.components.iter().as_slice()));
     }
-}
\ No newline at end of file
+}
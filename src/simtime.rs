@@ -20,6 +20,10 @@ impl SimTimeDelta {
     pub const fn from(delta: Duration) -> Self {
         SimTimeDelta {delta}
     }
+
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
 }
 
 pub const NO_DELTA: SimTimeDelta = SimTimeDelta { delta: Duration::from_secs(0) };
@@ -29,6 +33,12 @@ pub struct SimTime {
     time: Duration,
 }
 
+impl From<Duration> for SimTime {
+    fn from(time: Duration) -> Self {
+        SimTime { time }
+    }
+}
+
 impl std::ops::Add<SimTimeDelta> for SimTime {
     type Output = SimTime;
 
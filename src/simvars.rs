@@ -1,43 +1,81 @@
+use std::cell::Cell;
+
+use crate::clocks::LogicalClocks;
+use crate::drift::DriftingClock;
 use crate::scheduler::Scheduler;
 use crate::simtime::SimTime;
 use crate::environment::Environment;
+use crate::keys::ComponentId;
 
 // TODO: this is not THREAD SAFE
-// if it is possible to issue a warning on multithreaded access
-
-static mut INIT_GUARD: bool = false;
-pub static mut SIM: SimVars = SimVars { scheduler: std::ptr::null_mut() };
+// the scheduler is kept per thread, so independent simulations can run in separate
+// threads (e.g. tests), but a simulation can not be shared between threads
 
-pub struct SimVars {
-    scheduler: *mut Scheduler
+thread_local! {
+    static SCHEDULER: Cell<*mut Scheduler> = const { Cell::new(std::ptr::null_mut()) };
 }
 
+pub static mut SIM: SimVars = SimVars {};
+
+pub struct SimVars {}
+
 impl SimVars {
+    /// see `init_scheduler`
     pub fn init(&mut self) {
-        let s = Box::new(Scheduler::new());
-        self.scheduler = Box::leak(s);
-        unsafe {INIT_GUARD = true; }
+        init_scheduler();
     }
 }
 
+/// starts a new scheduler for the current thread
+///
+/// the scheduler of a simulation, explorer or harness that is still in use on this thread
+/// is replaced, only call it when no simulation is active. the previous scheduler is leaked,
+/// so references returned by `sim_sched()`, `sim_env()` and `sim_clocks()` stay valid
+pub fn init_scheduler() {
+    let s = Box::new(Scheduler::new());
+    SCHEDULER.with(|scheduler| scheduler.set(Box::leak(s)));
+}
+
+fn scheduler() -> *mut Scheduler {
+    let scheduler = SCHEDULER.with(|scheduler| scheduler.get());
+    assert!(!scheduler.is_null(), "simulation is not initialized");
+    scheduler
+}
+
 pub fn sim_sched() -> &'static mut Scheduler {
      unsafe {
-         assert!(INIT_GUARD);
-         &mut *SIM.scheduler
+         &mut *scheduler()
     }
 }
 
 pub fn sim_time() -> SimTime {
     unsafe {
-        assert!(INIT_GUARD);
-        (*SIM.scheduler).get_curr_time().clone()
+        (*scheduler()).get_curr_time().clone()
     }
 }
 
 pub fn sim_env() -> &'static mut Environment {
     unsafe {
-        assert!(INIT_GUARD);
-        &mut (*SIM.scheduler).env
+        &mut (*scheduler()).env
     }
 }
 
+/// name of the component, see `Simulation::set_name`
+pub fn sim_name(component: ComponentId) -> String {
+    unsafe {
+        (*scheduler()).names.name(component)
+    }
+}
+
+/// logical clocks of components, `None` unless enabled with `Simulation::enable_logical_clocks()`
+pub fn sim_clocks() -> Option<&'static LogicalClocks> {
+    unsafe {
+        (*scheduler()).clocks.as_ref()
+    }
+}
+
+/// local clock of the component, runs at the rate of simulation time unless
+/// drift is enabled with `Simulation::enable_clock_drift()`
+pub fn sim_local_clock(component: ComponentId) -> DriftingClock {
+    sim_sched().local_clocks.get(component.as_idx()).copied().unwrap_or_default()
+}
@@ -0,0 +1,431 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+use crate::component::Component;
+use crate::keys::{ChannelId, ComponentId};
+use crate::simtime::{NO_DELTA, SimTime};
+use crate::simvars::sim_sched;
+
+pub type SnapshotId = usize;
+
+/// marker sent by the simulation on every channel of a component that recorded its state
+#[derive(Debug)]
+pub(crate) struct Marker(pub SnapshotId);
+
+/// event that makes the initiator start a snapshot
+#[derive(Debug)]
+pub(crate) struct StartSnapshot(pub SnapshotId);
+
+/// message recorded in the state of a channel
+#[derive(Debug)]
+pub struct RecordedMessage {
+    /// time the message was received
+    pub time: SimTime,
+    /// copy of the message if its type was registered with `Simulation::register_snapshot_message`
+    pub message: Option<Box<dyn Any>>,
+}
+
+/// messages in transit on one direction of a channel
+#[derive(Debug)]
+pub struct ChannelState {
+    pub channel: ChannelId,
+    pub from: ComponentId,
+    pub to: ComponentId,
+    pub messages: Vec<RecordedMessage>,
+}
+
+impl ChannelState {
+    /// recorded messages of type `T`
+    pub fn messages<T: 'static>(&self) -> impl Iterator<Item=&T> {
+        self.messages.iter().filter_map(|m| m.message.as_ref().and_then(|m| m.downcast_ref::<T>()))
+    }
+}
+
+/// consistent global state collected with the Chandy-Lamport algorithm
+///
+/// a snapshot is incomplete if its initiator had crashed when it was due, or if a marker
+/// never arrived, e.g. because it was sent to or by a crashed component. the states of
+/// components that did not record are `None`
+#[derive(Debug)]
+pub struct GlobalSnapshot {
+    pub id: SnapshotId,
+    pub initiator: ComponentId,
+    pub started: SimTime,
+    /// time the last marker was received, `None` while the snapshot is being collected
+    pub completed: Option<SimTime>,
+    /// indexed by component id, see `Component::snapshot_state`
+    pub states: Vec<Option<Box<dyn Any>>>,
+    /// two entries for every channel, one per direction
+    pub channels: Vec<ChannelState>,
+}
+
+impl GlobalSnapshot {
+    pub fn is_complete(&self) -> bool {
+        self.completed.is_some()
+    }
+
+    pub fn state<T: 'static>(&self, component: ComponentId) -> Option<&T> {
+        self.states[component.as_idx()].as_ref().and_then(|s| s.downcast_ref::<T>())
+    }
+
+    /// state of the channel direction towards `to`
+    pub fn channel(&self, channel: ChannelId, to: ComponentId) -> &ChannelState {
+        let state = &self.channels[2 * channel.as_idx()];
+        if state.to == to { state } else { &self.channels[2 * channel.as_idx() + 1] }
+    }
+
+    /// number of recorded messages in transit
+    pub fn messages_in_transit(&self) -> usize {
+        self.channels.iter().map(|c| c.messages.len()).sum()
+    }
+}
+
+type CloneFn = fn(&dyn Any) -> Box<dyn Any>;
+
+/// snapshot being collected
+struct Collecting {
+    recorded: Vec<bool>,
+    /// indexed like `GlobalSnapshot::channels`, true while messages are recorded
+    recording: Vec<bool>,
+    pending_markers: usize,
+    snapshot: GlobalSnapshot,
+}
+
+/// runs the Chandy-Lamport algorithm on behalf of the components
+#[derive(Default)]
+pub(crate) struct SnapshotCollector {
+    requests: Vec<(SimTime, ComponentId)>,
+    cloners: HashMap<TypeId, CloneFn>,
+    endpoints: Vec<(ComponentId, ComponentId)>,
+    collecting: Vec<Option<Collecting>>,
+    /// snapshots of initiators that had crashed
+    aborted: Vec<GlobalSnapshot>,
+    snapshots: Vec<GlobalSnapshot>,
+}
+
+impl SnapshotCollector {
+    pub fn request(&mut self, time: SimTime, initiator: ComponentId) -> SnapshotId {
+        self.requests.push((time, initiator));
+        self.requests.len() - 1
+    }
+
+    pub fn register<T: Any + Clone>(&mut self) {
+        self.cloners.insert(TypeId::of::<T>(), |msg| Box::new(msg.downcast_ref::<T>().unwrap().clone()));
+    }
+
+    pub fn add_channel(&mut self, left: ComponentId, right: ComponentId) {
+        self.endpoints.push((left, right));
+    }
+
+    /// schedules requested snapshots, called after the scheduler is initialized
+    pub fn init(&mut self) {
+        self.collecting = self.requests.iter().map(|_| None).collect();
+        self.aborted.clear();
+        self.snapshots.clear();
+
+        for (id, (time, initiator)) in self.requests.iter().enumerate() {
+            let delay = *time - SimTime::default();
+            sim_sched().sched_component_event(delay, *initiator, *initiator, Box::new(StartSnapshot(id)));
+        }
+    }
+
+    pub fn snapshots(&self) -> &[GlobalSnapshot] {
+        &self.snapshots
+    }
+
+    /// snapshots that were started but not completed, in order of their ids
+    pub fn incomplete(&self) -> Vec<&GlobalSnapshot> {
+        let mut incomplete: Vec<&GlobalSnapshot> = self.collecting.iter().flatten().map(|c| &c.snapshot).collect();
+        incomplete.extend(self.aborted.iter());
+        incomplete.sort_by_key(|s| s.id);
+        incomplete
+    }
+
+    pub fn is_requested(&self) -> bool {
+        !self.requests.is_empty()
+    }
+
+    /// a snapshot has started and is not complete yet
+    pub fn is_collecting(&self) -> bool {
+        self.collecting.iter().any(|c| c.is_some())
+    }
+
+    pub fn start(&mut self, id: SnapshotId, initiator: ComponentId, components: &[Box<dyn Component>]) {
+        let now = *sim_sched().get_curr_time();
+        let mut collecting = self.collecting(id, initiator, now, components.len());
+
+        self.record(&mut collecting, initiator, components);
+        self.collecting[id] = Some(collecting);
+        self.complete_if_done(id, now);
+    }
+
+    /// keeps the snapshot of a crashed initiator as incomplete, nothing is recorded
+    pub fn abort(&mut self, id: SnapshotId, initiator: ComponentId, num_components: usize) {
+        let now = *sim_sched().get_curr_time();
+        let snapshot = self.collecting(id, initiator, now, num_components).snapshot;
+        self.aborted.push(snapshot);
+    }
+
+    fn collecting(&self, id: SnapshotId, initiator: ComponentId, now: SimTime, num_components: usize) -> Collecting {
+        let channels = self.endpoints.iter()
+            .enumerate()
+            .flat_map(|(idx, (left, right))| {
+                let channel = ChannelId::new(idx);
+                [ChannelState { channel, from: *right, to: *left, messages: vec![] },
+                 ChannelState { channel, from: *left, to: *right, messages: vec![] }]
+            })
+            .collect();
+
+        Collecting {
+            recorded: vec![false; num_components],
+            recording: vec![false; 2 * self.endpoints.len()],
+            pending_markers: 2 * self.endpoints.len(),
+            snapshot: GlobalSnapshot {
+                id,
+                initiator,
+                started: now,
+                completed: None,
+                states: (0..num_components).map(|_| None).collect(),
+                channels,
+            },
+        }
+    }
+
+    pub fn on_marker(&mut self, marker: Marker, channel: ChannelId, receiver: ComponentId, components: &[Box<dyn Component>]) {
+        let id = marker.0;
+        let mut collecting = self.collecting[id].take().expect("marker of unknown snapshot");
+
+        if !collecting.recorded[receiver.as_idx()] {
+            self.record(&mut collecting, receiver, components);
+        }
+
+        let direction = self.direction(channel, receiver);
+        collecting.recording[direction] = false;
+        collecting.pending_markers -= 1;
+
+        self.collecting[id] = Some(collecting);
+        self.complete_if_done(id, *sim_sched().get_curr_time());
+    }
+
+    /// records the message in all snapshots that are recording the channel
+    pub fn on_message(&mut self, channel: ChannelId, receiver: ComponentId, message: &dyn Any) {
+        let direction = self.direction(channel, receiver);
+
+        for collecting in self.collecting.iter_mut().flatten() {
+            if collecting.recording[direction] {
+                let message = self.cloners.get(&message.type_id()).map(|clone| clone(message));
+                let time = *sim_sched().get_curr_time();
+                collecting.snapshot.channels[direction].messages.push(RecordedMessage { time, message });
+            }
+        }
+    }
+
+    /// records the local state and sends markers on all channels of the component
+    fn record(&self, collecting: &mut Collecting, component: ComponentId, components: &[Box<dyn Component>]) {
+        collecting.recorded[component.as_idx()] = true;
+        collecting.snapshot.states[component.as_idx()] = components[component.as_idx()].snapshot_state();
+
+        for (idx, (left, right)) in self.endpoints.iter().enumerate() {
+            if *left != component && *right != component {
+                continue;
+            }
+
+            let channel = ChannelId::new(idx);
+            collecting.recording[self.direction(channel, component)] = true;
+            sim_sched().send_unstamped(NO_DELTA, component, channel, Box::new(Marker(collecting.snapshot.id)));
+        }
+    }
+
+    fn complete_if_done(&mut self, id: SnapshotId, now: SimTime) {
+        let done = self.collecting[id].as_ref().is_some_and(|c| c.pending_markers == 0);
+
+        if done {
+            let mut snapshot = self.collecting[id].take().unwrap().snapshot;
+            snapshot.completed = Some(now);
+            self.snapshots.push(snapshot);
+        }
+    }
+
+    /// index of the channel direction towards `receiver`
+    fn direction(&self, channel: ChannelId, receiver: ComponentId) -> usize {
+        let (left, _) = self.endpoints[channel.as_idx()];
+        if receiver == left { 2 * channel.as_idx() } else { 2 * channel.as_idx() + 1 }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::algorithms::leader_election::build_ring;
+    use crate::asynch::process::{AsynchComponent, AsynchProcess};
+    use crate::channels::delay_channel::{DelayChannel, DelayChannelBuilder};
+    use crate::channels::partial_synch_channel::{PartialSynchChannel, PartialSynchChannelBuilder};
+    use crate::component::{ComponentBase, ComponentBuilder};
+    use crate::scheduler::TieBreak;
+    use crate::sim::Simulation;
+    use crate::simtime::SimTimeDelta;
+    use crate::simvars::sim_time;
+
+    const BALANCE: u32 = 5;
+    const NUM_NODES: usize = 5;
+
+    #[derive(Debug, Clone)]
+    struct Token(u32);
+
+    /// forwards every token it receives until 100ms, the number of tokens is constant
+    struct Bank {
+        base: ComponentBase,
+        balance: u32,
+    }
+
+    impl AsynchProcess for Bank {
+        type Msg = Token;
+
+        fn get_sim_base(&self) -> &ComponentBase { &self.base }
+
+        fn get_sim_base_mut(&mut self) -> &mut ComponentBase { &mut self.base }
+
+        fn on_start(&mut self) {
+            self.balance -= 1;
+            self.send(self.channels()[0], Token(1));
+        }
+
+        fn on_message(&mut self, incoming_channel: ChannelId, msg: Token) {
+            self.balance += msg.0;
+            if sim_time() < SimTime::from(Duration::from_millis(100)) {
+                self.balance -= 1;
+                self.broadcast_except(incoming_channel, Token(1));
+            }
+        }
+
+        fn terminate(&mut self) {}
+
+        fn snapshot_state(&self) -> Option<Box<dyn Any>> {
+            Some(Box::new(self.balance))
+        }
+    }
+
+    struct BankBuilder;
+
+    impl ComponentBuilder for BankBuilder {
+        fn build_component(&mut self, id: ComponentId) -> Box<dyn Component> {
+            Box::new(AsynchComponent::new(Bank { base: ComponentBase::new(id), balance: BALANCE }))
+        }
+    }
+
+    fn check_snapshots(tie_break: TieBreak) {
+        let mut simulation = Simulation::<DelayChannel>::default();
+        let nodes = build_ring(&mut simulation, &mut BankBuilder, DelayChannelBuilder::new().delay_millis(10), NUM_NODES);
+        let ms = |millis| SimTime::from(Duration::from_millis(millis));
+        simulation.snapshot_at(ms(30), nodes[0]);
+        simulation.snapshot_at(ms(52), nodes[3]);
+        simulation.register_snapshot_message::<Token>();
+        simulation.set_tie_break(tie_break);
+
+        simulation.call_init();
+        simulation.run().unwrap();
+
+        assert_eq!(simulation.snapshots().len(), 2);
+        for snapshot in simulation.snapshots() {
+            let balances: u32 = nodes.iter().map(|c| snapshot.state::<u32>(*c).unwrap()).sum();
+            let in_transit: u32 = snapshot.channels.iter().flat_map(|c| c.messages::<Token>()).map(|t| t.0).sum();
+            assert!(in_transit > 0);
+            assert_eq!(balances + in_transit, BALANCE * NUM_NODES as u32);
+        }
+    }
+
+    #[test]
+    fn test_snapshot_is_consistent() {
+        check_snapshots(TieBreak::Fifo);
+    }
+
+    #[test]
+    fn test_snapshot_is_consistent_with_random_ties() {
+        // the first snapshot starts at a delivery time, markers and tokens are sent at the same time
+        for seed in 0..50 {
+            check_snapshots(TieBreak::Random { seed });
+        }
+    }
+
+    #[test]
+    fn test_crashes_leave_snapshots_incomplete() {
+        let mut simulation = Simulation::<DelayChannel>::default();
+        let nodes = build_ring(&mut simulation, &mut BankBuilder, DelayChannelBuilder::new().delay_millis(10), NUM_NODES);
+        let ms = |millis| SimTime::from(Duration::from_millis(millis));
+        simulation.crash_at(ms(30), nodes[0]);
+        simulation.crash_at(ms(40), nodes[2]);
+        // the initiator has crashed
+        simulation.snapshot_at(ms(35), nodes[0]);
+        // markers to the crashed node are lost
+        simulation.snapshot_at(ms(45), nodes[1]);
+
+        simulation.call_init();
+        simulation.run().unwrap();
+
+        assert!(simulation.snapshots().is_empty());
+        let incomplete = simulation.incomplete_snapshots();
+        assert_eq!(incomplete.len(), 2);
+        assert!(incomplete.iter().all(|s| !s.is_complete()));
+
+        assert_eq!((incomplete[0].initiator, incomplete[0].started), (nodes[0], ms(35)));
+        assert!(incomplete[0].states.iter().all(|s| s.is_none()));
+
+        assert_eq!(incomplete[1].initiator, nodes[1]);
+        assert!(incomplete[1].state::<u32>(nodes[1]).is_some());
+        assert!(incomplete[1].state::<u32>(nodes[2]).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "snapshots need fifo channels")]
+    fn test_non_fifo_channels_are_rejected() {
+        let mut simulation = Simulation::<PartialSynchChannel>::default();
+        let nodes = build_ring(&mut simulation, &mut BankBuilder, &mut PartialSynchChannelBuilder::new(), NUM_NODES);
+        simulation.snapshot_at(SimTime::default(), nodes[0]);
+
+        simulation.call_init();
+    }
+
+    /// sends a direct message to its neighbour on start
+    struct Direct {
+        base: ComponentBase,
+    }
+
+    impl AsynchProcess for Direct {
+        type Msg = ();
+
+        fn get_sim_base(&self) -> &ComponentBase { &self.base }
+
+        fn get_sim_base_mut(&mut self) -> &mut ComponentBase { &mut self.base }
+
+        fn on_start(&mut self) {
+            self.send_direct(ComponentId::new(1 - self.id().as_idx()), SimTimeDelta::from(Duration::from_millis(1)), ());
+        }
+
+        fn on_message(&mut self, _incoming_channel: ChannelId, _msg: ()) {}
+
+        fn terminate(&mut self) {}
+    }
+
+    struct DirectBuilder;
+
+    impl ComponentBuilder for DirectBuilder {
+        fn build_component(&mut self, id: ComponentId) -> Box<dyn Component> {
+            Box::new(AsynchComponent::new(Direct { base: ComponentBase::new(id) }))
+        }
+    }
+
+    #[test]
+    fn test_direct_messages_fail_snapshots() {
+        let mut simulation = Simulation::<DelayChannel>::default();
+        let a = simulation.add_component(&mut DirectBuilder);
+        let b = simulation.add_component(&mut DirectBuilder);
+        simulation.add_channel(DelayChannelBuilder::new().delay_millis(10), a, b);
+        simulation.snapshot_at(SimTime::default(), a);
+
+        simulation.call_init();
+        assert!(simulation.run().is_err());
+        assert_eq!(simulation.incomplete_snapshots().len(), 1);
+    }
+}
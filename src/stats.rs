@@ -0,0 +1,205 @@
+use std::any::{Any, TypeId};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use crate::keys::{ChannelId, ComponentId};
+use crate::simtime::SimTime;
+
+/// name used for messages of types that were not registered, their size is the size of
+/// the value in memory, heap data such as the contents of a `Vec` is not counted
+pub const UNREGISTERED: &str = "unregistered";
+
+type BitsFn = Box<dyn Fn(&dyn Any) -> usize>;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct TypeStats {
+    pub messages: usize,
+    pub bits: usize,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ComponentStats {
+    pub sent: usize,
+    pub bits_sent: usize,
+    pub received: usize,
+}
+
+/// message, bit and time complexity of a run
+#[derive(Debug, Clone, Default)]
+pub struct ComplexityReport {
+    /// messages sent by components
+    pub messages: usize,
+    pub bits: usize,
+    /// messages received by components
+    pub delivered: usize,
+    /// sent messages by channel, indexed by channel id
+    pub per_channel: Vec<usize>,
+    /// indexed by component id
+    pub per_component: Vec<ComponentStats>,
+    /// by the name given at registration, see `Simulation::register_message`
+    pub per_type: BTreeMap<String, TypeStats>,
+    /// time at which the last component terminated, if all terminated
+    pub termination_time: Option<SimTime>,
+    pub end_time: SimTime,
+}
+
+impl ComplexityReport {
+    pub fn max_messages_per_channel(&self) -> usize {
+        self.per_channel.iter().copied().max().unwrap_or(0)
+    }
+
+    /// termination time, or end of the run if not all components terminated
+    pub fn time(&self) -> SimTime {
+        self.termination_time.unwrap_or(self.end_time)
+    }
+
+    /// time in rounds of synchronous processes
+    pub fn rounds(&self) -> u64 {
+        self.time().as_rounds()
+    }
+}
+
+impl fmt::Display for ComplexityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "messages: {} ({} bits), delivered: {}", self.messages, self.bits, self.delivered)?;
+        writeln!(f, "max messages per channel: {}", self.max_messages_per_channel())?;
+        match self.termination_time {
+            Some(time) => writeln!(f, "terminated at {:?} (round {})", time.time(), self.rounds())?,
+            None => writeln!(f, "not terminated, run ended at {:?}", self.end_time.time())?,
+        }
+
+        for (name, stats) in self.per_type.iter() {
+            writeln!(f, "  {}: {} messages, {} bits", name, stats.messages, stats.bits)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// counts messages sent and received through the simulation
+#[derive(Default)]
+pub(crate) struct StatsCollector {
+    types: HashMap<TypeId, (String, BitsFn)>,
+    report: ComplexityReport,
+}
+
+impl StatsCollector {
+    pub fn register<T: Any>(&mut self, name: &str, bits: BitsFn) {
+        self.types.insert(TypeId::of::<T>(), (String::from(name), bits));
+    }
+
+    pub fn init(&mut self, num_components: usize, num_channels: usize) {
+        self.report = ComplexityReport {
+            per_channel: vec![0; num_channels],
+            per_component: vec![ComponentStats::default(); num_components],
+            ..ComplexityReport::default()
+        };
+    }
+
+    pub fn on_send(&mut self, sender: ComponentId, channel: ChannelId, message: &dyn Any) {
+        let (name, bits) = match self.types.get(&message.type_id()) {
+            Some((name, bits)) => (name.as_str(), bits(message)),
+            None => (UNREGISTERED, 8 * std::mem::size_of_val(message)),
+        };
+
+        let type_stats = self.report.per_type.entry(String::from(name)).or_default();
+        type_stats.messages += 1;
+        type_stats.bits += bits;
+
+        let report = &mut self.report;
+        report.messages += 1;
+        report.bits += bits;
+        report.per_channel[channel.as_idx()] += 1;
+        report.per_component[sender.as_idx()].sent += 1;
+        report.per_component[sender.as_idx()].bits_sent += bits;
+    }
+
+    pub fn on_receive(&mut self, receiver: ComponentId) {
+        self.report.delivered += 1;
+        self.report.per_component[receiver.as_idx()].received += 1;
+    }
+
+    pub fn report(&self, termination_time: Option<SimTime>, end_time: SimTime) -> ComplexityReport {
+        ComplexityReport { termination_time, end_time, ..self.report.clone() }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::channels::delay_channel::{DelayChannel, DelayChannelBuilder};
+    use crate::component::{ChannelLabel, Component, ComponentBuilder};
+    use crate::sim::Simulation;
+    use crate::simvars::sim_sched;
+
+    /// node 0 sends two values to node 1 and a string to node 2, node 1 acks every value
+    struct Node {
+        id: ComponentId,
+        channels: Vec<ChannelId>,
+    }
+
+    impl Component for Node {
+        fn sim_id(&self) -> ComponentId { self.id }
+
+        fn add_channel(&mut self, channel_id: ChannelId, _label: ChannelLabel) {
+            self.channels.push(channel_id);
+        }
+
+        fn init(&mut self) {
+            if self.id.as_idx() == 0 {
+                sim_sched().send_msg(self.id, self.channels[0], Box::new(7u32));
+                sim_sched().send_msg(self.id, self.channels[0], Box::new(8u32));
+                sim_sched().send_msg(self.id, self.channels[1], Box::new(String::from("unregistered")));
+            }
+        }
+
+        fn process_event(&mut self, _sender: ComponentId, _event: Box<dyn Any>) {}
+
+        fn receive_msg(&mut self, incoming_channel: ChannelId, msg: Box<dyn Any>) {
+            if msg.is::<u32>() {
+                sim_sched().send_msg(self.id, incoming_channel, Box::new(1u16));
+            }
+        }
+
+        fn terminate(&mut self) {}
+    }
+
+    struct NodeBuilder;
+
+    impl ComponentBuilder for NodeBuilder {
+        fn build_component(&mut self, id: ComponentId) -> Box<dyn Component> {
+            Box::new(Node { id, channels: vec![] })
+        }
+    }
+
+    #[test]
+    fn test_counts() {
+        let mut simulation = Simulation::<DelayChannel>::default();
+        let nodes: Vec<ComponentId> = (0..3).map(|_| simulation.add_component(&mut NodeBuilder)).collect();
+        let mut channel_builder = DelayChannelBuilder::with_delay(Duration::from_millis(1));
+        simulation.add_channel(&mut channel_builder, nodes[0], nodes[1]);
+        simulation.add_channel(&mut channel_builder, nodes[0], nodes[2]);
+        simulation.register_message::<u32>("value");
+        simulation.register_message_with_bits::<u16, _>("ack", |_| 1);
+
+        simulation.call_init();
+        simulation.run().unwrap();
+        let report = simulation.complexity();
+
+        // strings are not registered, their size does not depend on the contents
+        let string_bits = 8 * std::mem::size_of::<String>();
+        assert_eq!((report.messages, report.delivered), (5, 5));
+        assert_eq!(report.bits, 2 * 32 + 2 + string_bits);
+        assert_eq!(report.per_channel, [4, 1]);
+        assert_eq!(report.per_component, [
+            ComponentStats { sent: 3, bits_sent: 2 * 32 + string_bits, received: 2 },
+            ComponentStats { sent: 2, bits_sent: 2, received: 2 },
+            ComponentStats { sent: 0, bits_sent: 0, received: 1 },
+        ]);
+        assert_eq!(report.per_type.get("value"), Some(&TypeStats { messages: 2, bits: 64 }));
+        assert_eq!(report.per_type.get("ack"), Some(&TypeStats { messages: 2, bits: 2 }));
+        assert_eq!(report.per_type.get(UNREGISTERED), Some(&TypeStats { messages: 1, bits: string_bits }));
+    }
+}
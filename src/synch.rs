@@ -1,2 +1,4 @@
 pub mod channel;
-pub mod process;
\ No newline at end of file
+pub mod process;
+pub mod round;
+pub mod batch;
\ No newline at end of file
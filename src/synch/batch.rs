@@ -0,0 +1,74 @@
+use crate::component::ChannelLabel;
+use crate::keys::ChannelId;
+use crate::simvars::sim_sched;
+use crate::synch::process::ProcessId;
+use crate::synch::round::{Inbox, Round, RoundProcess};
+
+/// synchronous process with a single transition per round
+///
+/// follows the `msgs_i` / `trans_i` formalism: in every round the process gets the complete
+/// inbox of the round and returns messages to be sent, which are delivered at the start of the
+/// next round. like `RoundProcess`, batch processes are connected with `RoundChannel`s and run
+/// inside a `RoundDriver`
+pub trait BatchProcess {
+    type Msg: 'static;
+
+    fn id(&self) -> ProcessId;
+
+    fn add_channel(&mut self, channel_id: ChannelId, label: ChannelLabel);
+
+    fn init(&mut self) {}
+
+    /// executes one round
+    ///
+    /// # Arguments
+    ///
+    /// * `round`: current round, starting from zero
+    /// * `inbox`: all messages sent to this process in the previous round
+    ///
+    /// returns messages to send in this round
+    fn round(&mut self, round: Round, inbox: &[(ChannelId, Self::Msg)]) -> Vec<(ChannelId, Self::Msg)>;
+
+    fn is_halted(&self) -> bool;
+
+    fn terminate(&mut self);
+}
+
+impl<P: BatchProcess> RoundProcess for P {
+
+    fn id(&self) -> ProcessId {
+        BatchProcess::id(self)
+    }
+
+    fn add_channel(&mut self, channel_id: ChannelId, label: ChannelLabel) {
+        BatchProcess::add_channel(self, channel_id, label);
+    }
+
+    fn init(&mut self) {
+        BatchProcess::init(self);
+    }
+
+    fn round(&mut self, round: Round, inbox: Inbox) {
+        let inbox: Vec<(ChannelId, P::Msg)> = inbox.into_iter()
+            .map(|(channel, msg)| {
+                let msg = msg.downcast::<P::Msg>()
+                    .unwrap_or_else(|_| panic!("unexpected message type on channel {:?}", channel));
+                (channel, *msg)
+            })
+            .collect();
+
+        let outbox = BatchProcess::round(self, round, &inbox);
+
+        for (channel, msg) in outbox {
+            sim_sched().send_msg(BatchProcess::id(self), channel, Box::new(msg));
+        }
+    }
+
+    fn is_halted(&self) -> bool {
+        BatchProcess::is_halted(self)
+    }
+
+    fn terminate(&mut self) {
+        BatchProcess::terminate(self);
+    }
+}
@@ -67,6 +67,15 @@ pub trait SynchProcess: Component {
     );
 
     fn terminate(&mut self);
+
+    fn is_terminated(&self) -> bool {
+        false
+    }
+
+    /// local state recorded by a global snapshot
+    fn snapshot_state(&self) -> Option<Box<dyn Any>> {
+        None
+    }
 }
 
 impl<P: SynchProcess> Component for P {
@@ -87,7 +96,7 @@ impl<P: SynchProcess> Component for P {
     }
 
     fn process_event(&mut self, sender: ComponentId, _event: Box<dyn Any>) {
-        //TODO: what about the components that wake up on no input in rounds...
+        // processes that act in rounds without input should use synch::round::RoundProcess
 
         assert_eq!(self.id(), sender);
         assert!(self.get_curr_round().is_zero());
@@ -109,4 +118,12 @@ impl<P: SynchProcess> Component for P {
     fn terminate(&mut self) {
         SynchProcess::terminate(self);
     }
+
+    fn is_terminated(&self) -> bool {
+        SynchProcess::is_terminated(self)
+    }
+
+    fn snapshot_state(&self) -> Option<Box<dyn Any>> {
+        SynchProcess::snapshot_state(self)
+    }
 }
@@ -0,0 +1,240 @@
+use std::any::Any;
+use std::time::Duration;
+
+use crate::channel::{Channel, ChannelBuilder};
+use crate::component::{ChannelLabel, Component};
+use crate::keys::{ChannelId, ComponentId};
+use crate::names::short_type_name;
+use crate::simtime::{NO_DELTA, SimTimeDelta};
+use crate::simvars::{sim_sched, sim_time};
+use crate::synch::channel::ROUND_DELTA;
+use crate::synch::process::ProcessId;
+
+pub type Round = u64;
+
+/// messages received by a process at the start of a round, in order of arrival
+pub type Inbox = Vec<(ChannelId, Box<dyn Any>)>;
+
+/// messages are delivered half way through the round they were sent in,
+/// so they are in the inbox of the receiver before the next round starts
+pub const DELIVERY_DELTA: SimTimeDelta = SimTimeDelta::from(Duration::from_millis(500));
+
+// channel -------------------
+
+#[derive(Debug)]
+pub struct RoundChannel {
+    pub id: ChannelId,
+    pub left: ComponentId,
+    pub right: ComponentId,
+}
+
+impl Channel for RoundChannel {
+    fn accept_message_from(&mut self,
+                           source: ComponentId,
+                           message: Box<dyn Any>,
+    ) {
+        let dst: ComponentId;
+
+        if source == self.left {
+            dst = self.right;
+        } else if source == self.right {
+            dst = self.left;
+        } else {
+            panic!("unknown source {:?} for channel {:?}", source, self);
+        }
+
+        sim_sched().sched_receive_msg(DELIVERY_DELTA, dst, self.id, message);
+    }
+}
+
+#[derive(Default)]
+pub struct RoundChannelBuilder {}
+
+impl ChannelBuilder for RoundChannelBuilder {
+    type C = RoundChannel;
+
+    fn build_channel(&self, id: ChannelId, p0: ComponentId, p1: ComponentId) -> Self::C {
+        RoundChannel { id, left: p0, right: p1 }
+    }
+}
+
+// process -------------------
+
+/// process of the synchronous round model
+///
+/// the process is called at every round, including rounds in which it has not received any
+/// messages. messages sent in round r are delivered all together at the start of round r + 1.
+/// processes should be connected with `RoundChannel`s and wrapped in a `RoundDriver`
+pub trait RoundProcess {
+    fn id(&self) -> ProcessId;
+
+    fn add_channel(&mut self, channel_id: ChannelId, label: ChannelLabel);
+
+    fn init(&mut self) {}
+
+    /// executes one round, `inbox` holds all messages sent to this process in the previous round
+    /// (round zero always has an empty inbox)
+    fn round(&mut self, round: Round, inbox: Inbox);
+
+    /// halted process is not called in the following rounds and drops incoming messages
+    fn is_halted(&self) -> bool;
+
+    fn terminate(&mut self);
+}
+
+#[derive(Debug)]
+struct RoundTick(Round);
+
+/// component that calls wrapped process once per round
+pub struct RoundDriver<P: RoundProcess> {
+    process: P,
+    inbox: Inbox,
+}
+
+impl<P: RoundProcess> RoundDriver<P> {
+    pub fn new(process: P) -> Self {
+        RoundDriver { process, inbox: Vec::new() }
+    }
+
+    pub fn process(&self) -> &P {
+        &self.process
+    }
+
+    pub fn process_mut(&mut self) -> &mut P {
+        &mut self.process
+    }
+}
+
+impl<P: RoundProcess + 'static> Component for RoundDriver<P> {
+
+    fn sim_id(&self) -> ComponentId {
+        self.process.id()
+    }
+
+    fn type_name(&self) -> String {
+        short_type_name(std::any::type_name::<P>())
+    }
+
+    fn inner(&self) -> Option<&dyn Any> {
+        Some(&self.process)
+    }
+
+    fn inner_mut(&mut self) -> Option<&mut dyn Any> {
+        Some(&mut self.process)
+    }
+
+    fn add_channel(&mut self, channel_id: ChannelId, label: ChannelLabel) {
+        self.process.add_channel(channel_id, label);
+    }
+
+    fn init(&mut self) {
+        self.process.init();
+        sim_sched().sched_self_event_with_data(NO_DELTA, self.sim_id(), Box::new(RoundTick(0)));
+    }
+
+    fn process_event(&mut self, sender: ComponentId, event: Box<dyn Any>) {
+        assert_eq!(self.sim_id(), sender);
+        let RoundTick(round) = *event.downcast::<RoundTick>().unwrap();
+        assert_eq!(round, sim_time().as_rounds());
+
+        let inbox = std::mem::take(&mut self.inbox);
+        self.process.round(round, inbox);
+
+        if !self.process.is_halted() {
+            sim_sched().sched_self_event_with_data(ROUND_DELTA, self.sim_id(), Box::new(RoundTick(round + 1)));
+        }
+    }
+
+    fn receive_msg(&mut self, incoming_channel: ChannelId, msg: Box<dyn Any>) {
+        if self.process.is_halted() {
+            return;
+        }
+
+        self.inbox.push((incoming_channel, msg));
+    }
+
+    fn terminate(&mut self) {
+        self.process.terminate();
+    }
+
+    fn is_terminated(&self) -> bool {
+        self.process.is_halted()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::component::ComponentBuilder;
+    use crate::sim::Simulation;
+
+    const LAST_ROUND: Round = 3;
+
+    type Rounds = Rc<RefCell<Vec<(ComponentId, Round, Vec<Round>)>>>;
+
+    /// process 0 sends the round number in rounds 0 and 2, every process records its inboxes
+    struct Echo {
+        id: ProcessId,
+        channel: Option<ChannelId>,
+        rounds: Rounds,
+        halted: bool,
+    }
+
+    impl RoundProcess for Echo {
+        fn id(&self) -> ProcessId {
+            self.id
+        }
+
+        fn add_channel(&mut self, channel_id: ChannelId, _label: ChannelLabel) {
+            self.channel = Some(channel_id);
+        }
+
+        fn round(&mut self, round: Round, inbox: Inbox) {
+            let received = inbox.into_iter().map(|(_, msg)| *msg.downcast::<Round>().unwrap()).collect();
+            self.rounds.borrow_mut().push((self.id, round, received));
+
+            if self.id.as_idx() == 0 && [0, 2].contains(&round) {
+                sim_sched().send_msg(self.id, self.channel.unwrap(), Box::new(round));
+            }
+            self.halted = round == LAST_ROUND;
+        }
+
+        fn is_halted(&self) -> bool {
+            self.halted
+        }
+
+        fn terminate(&mut self) {}
+    }
+
+    struct EchoBuilder(Rounds);
+
+    impl ComponentBuilder for EchoBuilder {
+        fn build_component(&mut self, id: ComponentId) -> Box<dyn Component> {
+            Box::new(RoundDriver::new(Echo { id, channel: None, rounds: self.0.clone(), halted: false }))
+        }
+    }
+
+    #[test]
+    fn test_messages_arrive_next_round_and_empty_rounds_run() {
+        let rounds = Rounds::default();
+        let mut builder = EchoBuilder(rounds.clone());
+        let mut simulation = Simulation::<RoundChannel>::default();
+        let sender = simulation.add_component(&mut builder);
+        let receiver = simulation.add_component(&mut builder);
+        simulation.add_channel(&mut RoundChannelBuilder::default(), sender, receiver);
+
+        simulation.call_init();
+        simulation.run().unwrap();
+
+        let received: Vec<(Round, Vec<Round>)> = rounds.borrow().iter()
+            .filter(|(id, _, _)| *id == receiver)
+            .map(|(_, round, inbox)| (*round, inbox.clone()))
+            .collect();
+        // sent in rounds 0 and 2, rounds 0 and 2 have empty inboxes
+        assert_eq!(received, [(0, vec![]), (1, vec![0]), (2, vec![]), (3, vec![2])]);
+        assert_eq!(rounds.borrow().iter().filter(|(id, _, _)| *id == sender).count(), 4);
+    }
+}
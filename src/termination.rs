@@ -0,0 +1,189 @@
+use std::fmt;
+
+use crate::component::Component;
+use crate::keys::ComponentId;
+use crate::simtime::SimTime;
+
+/// outcome of the run with respect to termination of the components
+#[derive(Debug, Clone)]
+pub struct TerminationReport {
+    /// all components declared termination
+    pub all_terminated: bool,
+    pub non_terminated: Vec<ComponentId>,
+    /// time at which the last component declared termination
+    pub last_termination: Option<SimTime>,
+    /// messages that were sent but not received when the run ended
+    pub messages_in_flight: usize,
+    pub end_time: SimTime,
+}
+
+impl fmt::Display for TerminationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.all_terminated {
+            write!(f, "all components terminated")?;
+        } else {
+            write!(f, "{} component(s) did not terminate: {:?}", self.non_terminated.len(), self.non_terminated)?;
+        }
+
+        if let Some(time) = self.last_termination {
+            write!(f, ", last termination at {:?}", time.time())?;
+        }
+
+        write!(f, ", run ended at {:?} with {} message(s) in flight", self.end_time.time(), self.messages_in_flight)
+    }
+}
+
+/// tracks when components declare termination
+#[derive(Debug, Default)]
+pub(crate) struct TerminationDetector {
+    terminated_at: Vec<Option<SimTime>>,
+    num_terminated: usize,
+    last_termination: Option<SimTime>,
+}
+
+impl TerminationDetector {
+    pub fn init(&mut self, num_components: usize) {
+        self.terminated_at = vec![None; num_components];
+        self.num_terminated = 0;
+        self.last_termination = None;
+    }
+
+    /// records termination of the component if it has just terminated
+    pub fn update(&mut self, component: &dyn Component, now: SimTime) {
+        let idx = component.sim_id().as_idx();
+
+        if self.terminated_at[idx].is_none() && component.is_terminated() {
+            self.terminated_at[idx] = Some(now);
+            self.num_terminated += 1;
+            self.last_termination = Some(now);
+        }
+    }
+
+    pub fn all_terminated(&self) -> bool {
+        !self.terminated_at.is_empty() && self.num_terminated == self.terminated_at.len()
+    }
+
+    pub fn report(&self, messages_in_flight: usize, end_time: SimTime) -> TerminationReport {
+        TerminationReport {
+            all_terminated: self.all_terminated(),
+            non_terminated: self.terminated_at.iter()
+                .enumerate()
+                .filter(|(_, t)| t.is_none())
+                .map(|(idx, _)| ComponentId::new(idx))
+                .collect(),
+            last_termination: self.last_termination,
+            messages_in_flight,
+            end_time,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::any::Any;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::channels::delay_channel::{DelayChannel, DelayChannelBuilder};
+    use crate::component::{ChannelLabel, ComponentBuilder};
+    use crate::keys::ChannelId;
+    use crate::sim::Simulation;
+    use crate::simtime::SimTimeDelta;
+    use crate::simvars::sim_sched;
+
+    /// ticks every 10ms up to five times and terminates after `terminate_after` ticks
+    struct Ticker {
+        id: ComponentId,
+        ticks: u32,
+        terminate_after: u32,
+    }
+
+    impl Component for Ticker {
+        fn sim_id(&self) -> ComponentId { self.id }
+
+        fn add_channel(&mut self, _channel_id: ChannelId, _label: ChannelLabel) {}
+
+        fn init(&mut self) {
+            sim_sched().sched_self_event(SimTimeDelta::from(Duration::from_millis(10)), self.id);
+        }
+
+        fn process_event(&mut self, _sender: ComponentId, _event: Box<dyn Any>) {
+            self.ticks += 1;
+            if self.ticks < 5 {
+                sim_sched().sched_self_event(SimTimeDelta::from(Duration::from_millis(10)), self.id);
+            }
+        }
+
+        fn receive_msg(&mut self, _incoming_channel: ChannelId, _msg: Box<dyn Any>) {}
+
+        fn terminate(&mut self) {}
+
+        fn is_terminated(&self) -> bool {
+            self.ticks >= self.terminate_after
+        }
+    }
+
+    struct TickerBuilder(u32);
+
+    impl ComponentBuilder for TickerBuilder {
+        fn build_component(&mut self, id: ComponentId) -> Box<dyn Component> {
+            Box::new(Ticker { id, ticks: 0, terminate_after: self.0 })
+        }
+    }
+
+    fn ms(millis: u64) -> SimTime {
+        SimTime::from(Duration::from_millis(millis))
+    }
+
+    #[test]
+    fn test_run_stops_at_termination() {
+        let mut simulation = Simulation::<DelayChannel>::default();
+        simulation.add_component(&mut TickerBuilder(2));
+        simulation.add_component(&mut TickerBuilder(3));
+
+        simulation.call_init();
+        simulation.run().unwrap();
+
+        let report = simulation.termination_report();
+        assert!(report.all_terminated);
+        assert!(report.non_terminated.is_empty());
+        assert_eq!(report.last_termination, Some(ms(30)));
+        assert_eq!(report.end_time, ms(30));
+    }
+
+    #[test]
+    fn test_non_terminated_components_are_reported() {
+        let mut simulation = Simulation::<DelayChannel>::default();
+        simulation.add_component(&mut TickerBuilder(2));
+        let never = simulation.add_component(&mut TickerBuilder(u32::MAX));
+
+        simulation.call_init();
+        simulation.run().unwrap();
+
+        let report = simulation.termination_report();
+        assert!(!report.all_terminated);
+        assert_eq!(report.non_terminated, [never]);
+        assert_eq!(report.last_termination, Some(ms(20)));
+        assert_eq!(report.end_time, ms(50));
+        assert_eq!(report.to_string(), format!(
+            "1 component(s) did not terminate: [{:?}], last termination at 20ms, run ended at 50ms with 0 message(s) in flight", never));
+    }
+
+    #[test]
+    fn test_markers_are_not_in_flight() {
+        let mut simulation = Simulation::<DelayChannel>::default();
+        let a = simulation.add_component(&mut TickerBuilder(2));
+        let b = simulation.add_component(&mut TickerBuilder(3));
+        simulation.add_channel(&mut DelayChannelBuilder::with_delay(Duration::from_millis(100)), a, b);
+        simulation.snapshot_at(ms(5), a);
+
+        simulation.call_init();
+        simulation.run().unwrap();
+
+        // the run ends before the markers arrive
+        let report = simulation.termination_report();
+        assert!(report.all_terminated);
+        assert_eq!(report.messages_in_flight, 0);
+        assert_eq!(simulation.incomplete_snapshots().len(), 1);
+    }
+}
@@ -0,0 +1,326 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::time::Duration;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use crate::channel::Channel;
+use crate::explore::panic_message;
+use crate::scheduler::TieBreak;
+use crate::sim::Simulation;
+use crate::simtime::SimTime;
+use crate::topo::anchoredrandomgraph::AnchoredRandomGraphGen;
+use crate::topo::topo::Topology;
+use crate::topo::topogen::TopologyGenerator;
+
+#[cfg(feature = "proptest")]
+pub use self::strategy::scenarios;
+
+#[cfg(feature = "proptest")]
+mod strategy;
+#[cfg(feature = "quickcheck")]
+mod arbitrary;
+
+/// crashes of a scenario
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FaultPlan {
+    /// node of the topology and the time it crashes
+    pub crashes: Vec<(usize, SimTime)>,
+}
+
+/// random connected topology, tie break seed and faults of one test case
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scenario {
+    pub num_nodes: usize,
+    /// see `AnchoredRandomGraphGen`
+    pub connectivity: f64,
+    pub topo_seed: u64,
+    /// seed of `TieBreak::Random`
+    pub seed: u64,
+    pub faults: FaultPlan,
+}
+
+impl Scenario {
+    pub fn topology(&self) -> Topology {
+        let gen = AnchoredRandomGraphGen::new(self.num_nodes, self.connectivity);
+        TopologyGenerator::new(self.num_nodes, gen).seed(self.topo_seed).try_build_connected_network()
+    }
+
+    /// sets the tie break and schedules the crashes, call after `build_from_topo`
+    /// and before `call_init`
+    pub fn apply<C: Channel, V, E>(&self, simulation: &mut Simulation<C>, topo: &Topology<V, E>) {
+        simulation.set_tie_break(TieBreak::Random { seed: self.seed });
+
+        for (node, time) in self.faults.crashes.iter() {
+            let component = topo.component_id(*node).expect("topology is not built");
+            simulation.crash_at(*time, component);
+        }
+    }
+
+    /// smaller scenarios, simplest first: fewer crashes, fewer nodes, zero seeds
+    pub fn shrink(&self) -> Vec<Scenario> {
+        let mut smaller = Vec::new();
+
+        for idx in 0..self.faults.crashes.len() {
+            let mut s = self.clone();
+            s.faults.crashes.remove(idx);
+            smaller.push(s);
+        }
+
+        let mut sizes = vec![MIN_NODES, (MIN_NODES + self.num_nodes) / 2, self.num_nodes.saturating_sub(1)];
+        sizes.dedup();
+        for num_nodes in sizes.into_iter().filter(|n| *n >= MIN_NODES && *n < self.num_nodes) {
+            let mut s = self.clone();
+            s.num_nodes = num_nodes;
+            // crashes of removed nodes move to the last node, removing them is a separate step
+            for (node, _) in s.faults.crashes.iter_mut() {
+                *node = (*node).min(num_nodes - 1);
+            }
+            smaller.push(s);
+        }
+
+        if self.topo_seed != 0 {
+            smaller.push(Scenario { topo_seed: 0, ..self.clone() });
+        }
+        if self.seed != 0 {
+            smaller.push(Scenario { seed: 0, ..self.clone() });
+        }
+
+        for idx in 0..self.faults.crashes.len() {
+            let (node, time) = self.faults.crashes[idx];
+            if !time.is_zero() {
+                let mut s = self.clone();
+                s.faults.crashes[idx] = (node, SimTime::from(time.time() / 2));
+                smaller.push(s);
+            }
+        }
+
+        smaller
+    }
+}
+
+impl fmt::Display for Scenario {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} nodes, connectivity {}, topo seed {}, seed {}", self.num_nodes, self.connectivity, self.topo_seed, self.seed)?;
+        for (node, time) in self.faults.crashes.iter() {
+            write!(f, ", node {} crashes at {:?}", node, time.time())?;
+        }
+        Ok(())
+    }
+}
+
+/// the generator needs two nodes for a connected topology with a minimum degree of 1
+const MIN_NODES: usize = 2;
+
+/// generates random scenarios
+#[derive(Debug, Clone)]
+pub struct ScenarioGen {
+    nodes: RangeInclusive<usize>,
+    connectivity: f64,
+    max_crashes: usize,
+    crash_window: Duration,
+}
+
+impl Default for ScenarioGen {
+    fn default() -> Self {
+        ScenarioGen {
+            nodes: 2..=16,
+            connectivity: 0.2,
+            max_crashes: 0,
+            crash_window: Duration::from_secs(1),
+        }
+    }
+}
+
+impl ScenarioGen {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn nodes(&mut self, nodes: RangeInclusive<usize>) -> &mut Self {
+        assert!(*nodes.start() >= MIN_NODES, "scenarios need at least {} nodes", MIN_NODES);
+        self.nodes = nodes;
+        self
+    }
+
+    /// at most 0.5, denser graphs can not be generated for two nodes
+    pub fn connectivity(&mut self, connectivity: f64) -> &mut Self {
+        assert!(connectivity > 0.0 && connectivity <= 0.5, "connectivity must be in (0, 0.5]");
+        self.connectivity = connectivity;
+        self
+    }
+
+    pub fn max_crashes(&mut self, max_crashes: usize) -> &mut Self {
+        self.max_crashes = max_crashes;
+        self
+    }
+
+    /// crashes happen in `[0, window)`
+    pub fn crash_window(&mut self, window: Duration) -> &mut Self {
+        self.crash_window = window;
+        self
+    }
+
+    pub fn generate(&self, rng: &mut StdRng) -> Scenario {
+        let num_nodes = rng.gen_range(self.nodes.clone());
+        let num_crashes = rng.gen_range(0..=self.max_crashes.min(num_nodes));
+        let window = self.crash_window.as_nanos().max(1) as u64;
+        let crashes = (0..num_crashes)
+            .map(|_| (rng.gen_range(0..num_nodes), SimTime::from(Duration::from_nanos(rng.gen_range(0..window)))))
+            .collect();
+
+        Scenario {
+            num_nodes,
+            connectivity: self.connectivity,
+            topo_seed: rng.gen(),
+            seed: rng.gen(),
+            faults: FaultPlan { crashes },
+        }
+    }
+}
+
+/// failing scenario after shrinking
+#[derive(Debug, Clone)]
+pub struct Failure {
+    /// index of the failing case
+    pub case: usize,
+    pub original: Scenario,
+    pub scenario: Scenario,
+    pub message: String,
+    pub shrink_steps: usize,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "case {} failed: {}\n  shrunk in {} steps to: {}\n  original: {}",
+               self.case, self.message, self.shrink_steps, self.scenario, self.original)
+    }
+}
+
+/// runs the property on `cases` scenarios generated from `seed`, a panic fails the property
+///
+/// the first failing scenario is shrunk greedily: the first smaller scenario that still
+/// fails replaces it until no smaller scenario fails
+///
+/// invariants registered with `Simulation::add_invariant` stop the run with an error, the
+/// property names the violated invariant with `invariants_hold(&simulation)` after the run
+pub fn check<F>(gen: &ScenarioGen, cases: usize, seed: u64, property: F) -> Result<(), Box<Failure>>
+    where F: Fn(&Scenario) -> Result<(), String>
+{
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    for case in 0..cases {
+        let original = gen.generate(&mut rng);
+        if let Err(message) = run(&property, &original) {
+            let mut failure = Failure { case, original: original.clone(), scenario: original, message, shrink_steps: 0 };
+
+            while let Some((scenario, message)) = failure.scenario.shrink().into_iter()
+                .find_map(|s| run(&property, &s).err().map(|message| (s, message)))
+            {
+                failure.scenario = scenario;
+                failure.message = message;
+                failure.shrink_steps += 1;
+            }
+
+            return Err(Box::new(failure));
+        }
+    }
+
+    Ok(())
+}
+
+/// `Err` naming the first invariant that did not hold, see `Simulation::violation`
+pub fn invariants_hold<C: Channel>(simulation: &Simulation<C>) -> Result<(), String> {
+    match simulation.violation() {
+        Some(violation) => Err(format!("invariant {} violated at {:?}", violation.name, violation.time.time())),
+        None => Ok(()),
+    }
+}
+
+fn run<F>(property: &F, scenario: &Scenario) -> Result<(), String>
+    where F: Fn(&Scenario) -> Result<(), String>
+{
+    catch_unwind(AssertUnwindSafe(|| property(scenario)))
+        .unwrap_or_else(|payload| Err(format!("panic: {}", panic_message(payload))))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algorithms::mst::ghs::GhsBuilder;
+    use crate::algorithms::mst::verify_mst;
+    use crate::channels::delay_channel::{DelayChannel, DelayChannelBuilder};
+    use crate::invariant::CheckSchedule;
+
+    fn ghs_finds_mst(scenario: &Scenario) -> Result<(), String> {
+        ghs_run(scenario, |_| {})
+    }
+
+    fn ghs_run<F>(scenario: &Scenario, setup: F) -> Result<(), String>
+        where F: FnOnce(&mut Simulation<DelayChannel>)
+    {
+        let mut topo = scenario.topology();
+        let mut builder = GhsBuilder::new(DelayChannelBuilder::with_delay(Duration::from_millis(10)));
+        let mut simulation = Simulation::<DelayChannel>::default();
+        simulation.build_from_topo(&mut topo, &mut builder);
+        builder.set_weights(&topo);
+        scenario.apply(&mut simulation, &topo);
+        setup(&mut simulation);
+
+        simulation.call_init();
+        let result = simulation.run();
+        invariants_hold(&simulation)?;
+        result.map_err(|e| format!("{:?}", e))?;
+        if !simulation.termination_report().all_terminated {
+            return Err(String::from("not all nodes terminated"));
+        }
+        verify_mst(&topo, &builder.tree().borrow().branches).map(|_| ())
+    }
+
+    #[test]
+    fn test_ghs_on_random_scenarios() {
+        let mut gen = ScenarioGen::new();
+        gen.nodes(2..=12);
+        check(&gen, 10, 7, ghs_finds_mst).unwrap();
+    }
+
+    #[test]
+    fn test_failure_is_shrunk() {
+        let mut gen = ScenarioGen::new();
+        gen.nodes(2..=20).max_crashes(3);
+
+        // fails for crashes in scenarios of more than 4 nodes
+        let failure = check(&gen, 50, 1, |s| {
+            match s.num_nodes > 4 && !s.faults.crashes.is_empty() {
+                true => Err(String::from("crash")),
+                false => Ok(()),
+            }
+        }).unwrap_err();
+
+        assert_eq!(failure.scenario.num_nodes, 5);
+        assert_eq!(failure.scenario.faults.crashes, vec![(failure.scenario.faults.crashes[0].0, SimTime::default())]);
+        assert_eq!((failure.scenario.topo_seed, failure.scenario.seed), (0, 0));
+    }
+
+    #[test]
+    fn test_invariant_fails_property() {
+        let mut gen = ScenarioGen::new();
+        gen.nodes(2..=12);
+
+        let failure = check(&gen, 10, 7, |s| ghs_run(s, |simulation| {
+            simulation.add_invariant("fewer than 4 nodes", CheckSchedule::EveryEvent,
+                                     |components, _| components.len() < 4);
+        })).unwrap_err();
+
+        assert_eq!(failure.scenario.num_nodes, 4);
+        assert!(failure.message.starts_with("invariant fewer than 4 nodes violated"), "{}", failure.message);
+    }
+
+    #[test]
+    #[should_panic(expected = "connectivity")]
+    fn test_connectivity_is_bounded() {
+        ScenarioGen::new().connectivity(0.6);
+    }
+}
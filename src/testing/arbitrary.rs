@@ -0,0 +1,16 @@
+use quickcheck::{Arbitrary, Gen};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use crate::testing::{Scenario, ScenarioGen};
+
+/// scenarios of the default `ScenarioGen`, shrinks with `Scenario::shrink`
+impl Arbitrary for Scenario {
+    fn arbitrary(g: &mut Gen) -> Self {
+        ScenarioGen::default().generate(&mut StdRng::seed_from_u64(u64::arbitrary(g)))
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        Box::new(Scenario::shrink(self).into_iter())
+    }
+}
@@ -0,0 +1,28 @@
+use std::time::Duration;
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+use crate::simtime::SimTime;
+use crate::testing::{FaultPlan, Scenario, ScenarioGen};
+
+/// proptest strategy for the scenarios of the generator, shrinks like the generated values
+pub fn scenarios(gen: &ScenarioGen) -> impl Strategy<Value = Scenario> {
+    let gen = gen.clone();
+    let window = gen.crash_window.as_nanos().max(1) as u64;
+
+    (gen.nodes.clone(), any::<u64>(), any::<u64>())
+        .prop_flat_map(move |(num_nodes, topo_seed, seed)| {
+            let connectivity = gen.connectivity;
+            let crash = (0..num_nodes, 0..window);
+            vec(crash, 0..=gen.max_crashes.min(num_nodes)).prop_map(move |crashes| Scenario {
+                num_nodes,
+                connectivity,
+                topo_seed,
+                seed,
+                faults: FaultPlan {
+                    crashes: crashes.into_iter().map(|(node, t)| (node, SimTime::from(Duration::from_nanos(t)))).collect(),
+                },
+            })
+        })
+}
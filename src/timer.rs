@@ -0,0 +1,228 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use crate::keys::ComponentId;
+use crate::simtime::SimTimeDelta;
+use crate::simvars::sim_sched;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct TimerId(usize);
+
+/// event scheduled for the owner of a timer
+#[derive(Debug)]
+pub(crate) struct Timeout(TimerId);
+
+/// typed timers of an `AsynchProcess`, see `AsynchProcess::start_timer`
+pub trait TimerHandler<T> {
+    fn on_timer(&mut self, id: TimerId, timer: T);
+}
+
+type CloneFn = fn(&dyn Any) -> Box<dyn Any>;
+
+struct Timer {
+    data: Box<dyn Any>,
+    /// period, jitter and clone of the data for periodic timers
+    periodic: Option<(SimTimeDelta, SimTimeDelta, CloneFn)>,
+}
+
+/// timers of a component
+///
+/// a timer is a self event that only carries its id, the data stays in the table until
+/// the timer expires. cancelled timers are dropped when their event is processed
+#[derive(Default)]
+pub struct Timers {
+    next_id: usize,
+    timers: HashMap<TimerId, Timer>,
+    handlers: HashMap<TypeId, Box<dyn Any>>,
+    seed: u64,
+    rng: Option<StdRng>,
+}
+
+impl fmt::Debug for Timers {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Timers").field("pending", &self.timers.len()).finish()
+    }
+}
+
+impl Timers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// seed of the jitter of periodic timers, combined with the id of the component
+    pub fn seed(&mut self, seed: u64) -> &mut Self {
+        self.seed = seed;
+        self.rng = None;
+        self
+    }
+
+    /// one-shot timer expiring after `delay`
+    pub fn start<T: 'static>(&mut self, owner: ComponentId, delay: SimTimeDelta, data: T) -> TimerId {
+        self.add(owner, delay, Timer { data: Box::new(data), periodic: None })
+    }
+
+    /// timer expiring every `period` plus a random delay of up to `jitter`, until cancelled
+    pub fn start_periodic<T: Clone + 'static>(&mut self, owner: ComponentId, period: SimTimeDelta, jitter: SimTimeDelta, data: T) -> TimerId {
+        let clone: CloneFn = |data| Box::new(data.downcast_ref::<T>().unwrap().clone());
+        let delay = self.jittered(owner, period, jitter);
+        self.add(owner, delay, Timer { data: Box::new(data), periodic: Some((period, jitter, clone)) })
+    }
+
+    /// returns false if the timer already expired or was cancelled
+    pub fn cancel(&mut self, id: TimerId) -> bool {
+        self.timers.remove(&id).is_some()
+    }
+
+    pub fn is_pending(&self, id: TimerId) -> bool {
+        self.timers.contains_key(&id)
+    }
+
+    pub fn num_pending(&self) -> usize {
+        self.timers.len()
+    }
+
+    pub fn is_timeout(event: &dyn Any) -> bool {
+        event.is::<Timeout>()
+    }
+
+    /// timer expiring with the event and its data, `None` if the timer was cancelled.
+    /// periodic timers are started again with a copy of the data
+    pub fn expire(&mut self, owner: ComponentId, event: Box<dyn Any>) -> Option<(TimerId, Box<dyn Any>)> {
+        let Timeout(id) = *event.downcast::<Timeout>().expect("event is not a timeout");
+
+        match self.timers.get(&id)?.periodic {
+            Some((period, jitter, clone)) => {
+                let data = clone(self.timers[&id].data.as_ref());
+                let delay = self.jittered(owner, period, jitter);
+                sim_sched().sched_self_event_with_data(delay, owner, Box::new(Timeout(id)));
+                Some((id, data))
+            }
+            None => self.timers.remove(&id).map(|timer| (id, timer.data)),
+        }
+    }
+
+    pub(crate) fn set_handler<H: 'static>(&mut self, data: TypeId, handler: H) {
+        self.handlers.insert(data, Box::new(handler));
+    }
+
+    pub(crate) fn handler<H: Copy + 'static>(&self, data: &dyn Any) -> Option<H> {
+        self.handlers.get(&data.type_id()).and_then(|h| h.downcast_ref::<H>()).copied()
+    }
+
+    fn add(&mut self, owner: ComponentId, delay: SimTimeDelta, timer: Timer) -> TimerId {
+        let id = TimerId(self.next_id);
+        self.next_id += 1;
+        self.timers.insert(id, timer);
+        sim_sched().sched_self_event_with_data(delay, owner, Box::new(Timeout(id)));
+        id
+    }
+
+    fn jittered(&mut self, owner: ComponentId, period: SimTimeDelta, jitter: SimTimeDelta) -> SimTimeDelta {
+        if jitter.delta().is_zero() {
+            return period;
+        }
+
+        let seed = self.seed ^ owner.as_idx() as u64;
+        let rng = self.rng.get_or_insert_with(|| StdRng::seed_from_u64(seed));
+        let jitter = Duration::from_nanos(rng.gen_range(0..=jitter.delta().as_nanos() as u64));
+        SimTimeDelta::from(period.delta() + jitter)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::asynch::process::{AsynchComponent, AsynchProcess};
+    use crate::channels::delay_channel::DelayChannel;
+    use crate::component::{Component, ComponentBase, ComponentBuilder};
+    use crate::keys::ChannelId;
+    use crate::sim::Simulation;
+    use crate::simtime::SimTime;
+    use crate::simvars::sim_time;
+
+    #[derive(Debug, Clone)]
+    struct Hello;
+
+    #[derive(Debug)]
+    struct Hold(u32);
+
+    type Expired = Rc<RefCell<Vec<(SimTime, String)>>>;
+
+    struct Node {
+        base: ComponentBase,
+        hellos: usize,
+        expired: Expired,
+    }
+
+    impl AsynchProcess for Node {
+        type Msg = ();
+
+        fn get_sim_base(&self) -> &ComponentBase { &self.base }
+
+        fn get_sim_base_mut(&mut self) -> &mut ComponentBase { &mut self.base }
+
+        fn on_start(&mut self) {
+            let ms = |millis| SimTimeDelta::from(Duration::from_millis(millis));
+            self.start_periodic_timer(ms(10), ms(2), Hello);
+            let cancelled = self.start_timer(ms(5), Hold(0));
+            self.start_timer(ms(25), Hold(1));
+            assert!(self.cancel_timer(cancelled));
+            assert!(!self.timers().is_pending(cancelled));
+        }
+
+        fn on_message(&mut self, _incoming_channel: ChannelId, _msg: ()) {}
+
+        fn terminate(&mut self) {}
+    }
+
+    impl TimerHandler<Hello> for Node {
+        fn on_timer(&mut self, id: TimerId, _timer: Hello) {
+            self.expired.borrow_mut().push((sim_time(), String::from("hello")));
+            self.hellos += 1;
+            if self.hellos == 5 {
+                self.cancel_timer(id);
+            }
+        }
+    }
+
+    impl TimerHandler<Hold> for Node {
+        fn on_timer(&mut self, _id: TimerId, timer: Hold) {
+            self.expired.borrow_mut().push((sim_time(), format!("hold {}", timer.0)));
+        }
+    }
+
+    struct NodeBuilder(Expired);
+
+    impl ComponentBuilder for NodeBuilder {
+        fn build_component(&mut self, id: ComponentId) -> Box<dyn Component> {
+            Box::new(AsynchComponent::new(Node { base: ComponentBase::new(id), hellos: 0, expired: self.0.clone() }))
+        }
+    }
+
+    #[test]
+    fn test_periodic_and_cancelled_timers() {
+        let expired = Expired::default();
+        let mut simulation = Simulation::<DelayChannel>::default();
+        simulation.add_component(&mut NodeBuilder(expired.clone()));
+        simulation.call_init();
+        simulation.run().unwrap();
+
+        let expired = expired.borrow();
+        let hellos: Vec<Duration> = expired.iter().filter(|(_, name)| name == "hello").map(|(t, _)| t.time()).collect();
+        assert_eq!(hellos.len(), 5);
+        for (prev, next) in [Duration::ZERO].iter().chain(hellos.iter()).zip(hellos.iter()) {
+            let gap = *next - *prev;
+            assert!(gap >= Duration::from_millis(10) && gap <= Duration::from_millis(12), "{:?}", gap);
+        }
+        assert!(expired.contains(&(SimTime::from(Duration::from_millis(25)), String::from("hold 1"))));
+        assert!(!expired.iter().any(|(_, name)| name == "hold 0"));
+    }
+}
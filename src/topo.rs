@@ -1,6 +1,6 @@
 // TODO: feature to include topology
 
-pub use crate::topo::sim::FromGraphBuilder;
+pub use crate::topo::sim::{FromGraphBuilder, TOPOLOGY};
 
 pub mod topo;
 pub mod topogen;
@@ -3,6 +3,7 @@
 
 use petgraph::graph::NodeIndex;
 use rand::Rng;
+use rand::rngs::StdRng;
 use crate::topo::topodecl::{TopoEdge, TopoGraph};
 use crate::topo::topogen::EdgesGenerator;
 // use crate::topo::topogen::EdgesGenerator;
@@ -40,8 +41,7 @@ impl EdgesGenerator for AnchoredRandomGraphGen {
         self.num_edges
     }
 
-    fn generate_edges(&mut self, g: &mut TopoGraph<(),()>) {
-        let mut rng = rand::thread_rng();
+    fn generate_edges(&mut self, g: &mut TopoGraph<(),()>, rng: &mut StdRng) {
 
         let indices: Vec<NodeIndex> = g.node_indices().collect();
 
@@ -54,7 +54,8 @@ impl EdgesGenerator for AnchoredRandomGraphGen {
                     let tail_idx = indices[rng.gen_range(0..self.num_nodes)];
                     if tail_idx != head_idx && g.find_edge(head_idx, tail_idx).is_none() {
                         g.add_edge(head_idx, tail_idx, TopoEdge::default());
-                        edges_left-= 1;
+                        // small graphs may need more edges for the minimum degree than num_edges
+                        edges_left = edges_left.saturating_sub(1);
                         break;
                     }
                 }
@@ -1,4 +1,5 @@
 use crate::topo::topo::{Topology};
+use crate::names::ComponentNames;
 use petgraph::dot::Dot;
 use petgraph::dot::Config::{EdgeNoLabel, NodeNoLabel};
 
@@ -7,21 +8,39 @@ impl<V, E> Topology<V, E>
     where V: std::fmt::Debug, E: std::fmt::Debug
 {
     pub fn dot(topo: &Topology<V, E>) {
-        let dot = Dot::with_attr_getters(
+        Self::dot_with_names(topo, &ComponentNames::default());
+    }
+
+    /// labels nodes with the names of their components, see `Simulation::names`
+    pub fn dot_with_names(topo: &Topology<V, E>, names: &ComponentNames) {
+        // the attribute closures are borrowed by `Dot`, format it in the same statement
+        eprintln!("dot = {:?}", Dot::with_attr_getters(
             &topo.g,
             &[NodeNoLabel, EdgeNoLabel],
             &|_, e| {
-                String::from(format!("weigh={:.3}", e.weight().distance))
+                format!("weigh={:.3}", e.weight().distance)
             },
             &|_, n| {
-                return match n.1.component_id {
-                    None => {String::new()},
-                    Some(id) => { String::from(format!("label=\"{:?}\"", id))}
+                match n.1.component_id {
+                    None => String::new(),
+                    Some(id) => format!("label=\"{}\"", escape_label(&names.name(id))),
                 }
-
             }
-        );
+        ));
+    }
+}
+
+/// escapes quotes and backslashes of a quoted DOT string
+fn escape_label(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
 
-        eprintln!("dot = {:?}", dot);
+    #[test]
+    fn test_escape_label() {
+        assert_eq!(escape_label(r#"net "a"\b"#), r#"net \"a\"\\b"#);
     }
-}
\ No newline at end of file
+}
@@ -1,7 +1,11 @@
 use crate::channel::{Channel, ChannelBuilder};
 use crate::component::ComponentBuilder;
+use crate::environment::Key;
 use crate::sim::Simulation;
-use crate::topo::topo::Topology;
+use crate::topo::topo::{Topology, TopologyInfo};
+
+/// topology published by `Simulation::build_from_topo`, read it with `sim_env().get(TOPOLOGY)`
+pub const TOPOLOGY: Key<TopologyInfo> = Key::new("topology");
 
 pub trait FromGraphBuilder {
     type Node;
@@ -13,8 +17,11 @@ pub trait FromGraphBuilder {
 
 impl<ChannelT: Channel> Simulation<ChannelT> {
 
+    /// adds a component for every node and a channel for every edge of the topology,
+    /// the topology records the assigned component and channel ids and is published
+    /// in the environment under `TOPOLOGY`
     pub fn build_from_topo<V, E, NB>(&mut self,
-                                     mut topo: Topology<V, E>,
+                                     topo: &mut Topology<V, E>,
                                      builder: &mut NB
     )
         where NB: ComponentBuilder + ChannelBuilder<C = ChannelT>
@@ -36,7 +43,6 @@ impl<ChannelT: Channel> Simulation<ChannelT> {
             g[idx].channle_id = Some(channel_id);
         }
 
-        // TODO: add value in env
-        // sim_env().add_value(String::from("topology"), Box::new(topo));
+        self.env_mut().insert(TOPOLOGY, topo.info());
     }
 }
\ No newline at end of file
@@ -1,13 +1,69 @@
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+
+use crate::keys::{ChannelId, ComponentId};
 use crate::topo::topodecl::TopoGraph;
 
 pub struct Topology<V=(), E=()> {
-    pub(super) g: TopoGraph<V, E>,
+    pub(crate) g: TopoGraph<V, E>,
 }
 
 impl<V, E> Topology<V, E> {
     pub(crate) fn new() -> Self {
         Self {g: TopoGraph::new_undirected()}
     }
+
+    pub fn num_nodes(&self) -> usize {
+        self.g.node_count()
+    }
+
+    pub fn num_edges(&self) -> usize {
+        self.g.edge_count()
+    }
+
+    /// component built for the node, after `Simulation::build_from_topo`
+    pub fn component_id(&self, node: usize) -> Option<ComponentId> {
+        self.g.node_weight(NodeIndex::new(node)).and_then(|n| n.component_id)
+    }
+
+    /// components and channels of the topology, after `Simulation::build_from_topo`
+    pub fn info(&self) -> TopologyInfo {
+        let links = self.g.edge_references()
+            .filter_map(|e| {
+                let left = self.g[e.source()].component_id?;
+                let right = self.g[e.target()].component_id?;
+                Some(Link { left, right, channel: e.weight().channle_id?, distance: e.weight().distance })
+            })
+            .collect();
+
+        TopologyInfo { num_nodes: self.num_nodes(), links }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Link {
+    pub left: ComponentId,
+    pub right: ComponentId,
+    pub channel: ChannelId,
+    pub distance: f64,
+}
+
+/// topology of the simulated network, published in the environment by `build_from_topo`
+#[derive(Debug, Clone, Default)]
+pub struct TopologyInfo {
+    pub num_nodes: usize,
+    pub links: Vec<Link>,
+}
+
+impl TopologyInfo {
+    /// neighbors of the component and the channels leading to them
+    pub fn neighbors(&self, component: ComponentId) -> impl Iterator<Item=(ComponentId, ChannelId)> + '_ {
+        self.links.iter().filter_map(move |l| match component {
+            c if c == l.left => Some((l.right, l.channel)),
+            c if c == l.right => Some((l.left, l.channel)),
+            _ => None,
+        })
+    }
 }
 
 // impl<V, E> Topology<V, E>{
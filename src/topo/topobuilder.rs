@@ -32,9 +32,13 @@ impl<V, E> TopologyBuilder<V, E> {
     }
 
     pub fn add_edge(&mut self, from: usize, to: usize, data: E) {
+        self.add_edge_with_distance(from, to, 0.0, data);
+    }
+
+    pub fn add_edge_with_distance(&mut self, from: usize, to: usize, distance: f64, data: E) {
         let edge = TopoEdge {
             channle_id: None,
-            distance: 0.0,
+            distance,
             data: data,
         };
 
@@ -1,11 +1,12 @@
 use crate::topo::topo::{Topology};
 use petgraph::algo::connected_components;
-use rand::{thread_rng, Rng};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
 use crate::topo::topodecl::{Point, TopoGraph, TopoNode};
 
 pub trait EdgesGenerator {
     fn estimated_edges_count(&mut self) -> usize;
-    fn generate_edges(&mut self, g: &mut TopoGraph<(),()>);
+    fn generate_edges(&mut self, g: &mut TopoGraph<(),()>, rng: &mut StdRng);
 }
 
 pub struct TopologyGenerator<EdgesGeneratorT>
@@ -13,11 +14,18 @@ pub struct TopologyGenerator<EdgesGeneratorT>
 {
     edge_strategy: EdgesGeneratorT,
     num_nodes: usize,
+    rng: StdRng,
 }
 
 impl<EdgesGeneratorT: EdgesGenerator>  TopologyGenerator<EdgesGeneratorT> {
     pub fn new(num_nodes: usize, edge_strategy: EdgesGeneratorT) -> TopologyGenerator<EdgesGeneratorT>{
-        TopologyGenerator{ edge_strategy, num_nodes }
+        TopologyGenerator{ edge_strategy, num_nodes, rng: StdRng::from_entropy() }
+    }
+
+    /// generates the same topologies for the same seed
+    pub fn seed(&mut self, seed: u64) -> &mut Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
     }
 
     pub fn try_build_connected_network(&mut self) -> Topology {
@@ -27,7 +35,7 @@ impl<EdgesGeneratorT: EdgesGenerator>  TopologyGenerator<EdgesGeneratorT> {
         self.generate_nodes(&mut g);
 
         for _ in 0..MAX_ITER {
-            self.edge_strategy.generate_edges(&mut g);
+            self.edge_strategy.generate_edges(&mut g, &mut self.rng);
 
             if connected_components(&g) == 1 {
                 self.set_edge_weight(&mut g);
@@ -44,7 +52,7 @@ impl<EdgesGeneratorT: EdgesGenerator>  TopologyGenerator<EdgesGeneratorT> {
     pub fn build_network(&mut self) -> Topology {
         let mut g = TopoGraph::with_capacity(self.num_nodes, self.edge_strategy.estimated_edges_count());
         self.generate_nodes(&mut g);
-        self.edge_strategy.generate_edges(&mut g);
+        self.edge_strategy.generate_edges(&mut g, &mut self.rng);
         self.set_edge_weight(&mut g);
         return Topology {g: g};
     }
@@ -52,11 +60,9 @@ impl<EdgesGeneratorT: EdgesGenerator>  TopologyGenerator<EdgesGeneratorT> {
     //---------------------------------------------------------------------------------
 
     fn generate_nodes(&mut self, g: &mut TopoGraph<(), ()>) {
-        let mut rng = thread_rng();
-
         for _ in 0..self.num_nodes {
-            let x = rng.gen();
-            let y = rng.gen();
+            let x = self.rng.gen();
+            let y = self.rng.gen();
             g.add_node(TopoNode{component_id: None, position: Point {x, y}, data: () });
         }
     }
@@ -0,0 +1,83 @@
+pub use self::recorder::Envelope;
+
+use crate::clocks::ClockStamp;
+use crate::keys::{ChannelId, ComponentId};
+use crate::names::ComponentNames;
+use crate::scheduler::ScheduleDecision;
+use crate::simtime::SimTime;
+
+pub(crate) mod recorder;
+pub mod shiviz;
+pub mod svg;
+
+/// identifier of a message assigned when the message is sent
+pub type MsgId = usize;
+
+#[derive(Debug, Clone)]
+pub enum TraceEventKind {
+    /// component processed an event (timer, start event, ...) scheduled by `sender`
+    Local { sender: ComponentId },
+    /// component sent a message on the channel
+    Send { channel: ChannelId, msg_id: MsgId },
+    /// component received a message, messages scheduled without going through
+    /// the simulation (e.g. directly from channels) do not have an id
+    Receive { channel: ChannelId, msg_id: Option<MsgId> },
+}
+
+#[derive(Debug, Clone)]
+pub struct TraceRecord {
+    pub time: SimTime,
+    pub component: ComponentId,
+    pub kind: TraceEventKind,
+    /// logical clocks of the component after the event, if clocks are enabled
+    pub clocks: Option<ClockStamp>,
+}
+
+/// events recorded during a run, in order of execution
+#[derive(Debug, Default)]
+pub struct Trace {
+    names: ComponentNames,
+    records: Vec<TraceRecord>,
+    decisions: Vec<ScheduleDecision>,
+}
+
+impl Trace {
+    pub fn num_components(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn records(&self) -> &[TraceRecord] {
+        &self.records
+    }
+
+    /// ordering decisions between events scheduled for the same time,
+    /// replay them with `TieBreak::replay`
+    pub fn decisions(&self) -> &[ScheduleDecision] {
+        &self.decisions
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// name of the component used in exported traces, see `Simulation::set_name`
+    pub fn component_name(&self, component: ComponentId) -> String {
+        self.names.name(component)
+    }
+
+    pub(crate) fn set_names(&mut self, names: ComponentNames) {
+        self.names = names;
+    }
+
+    pub(crate) fn push(&mut self, record: TraceRecord) {
+        self.records.push(record);
+    }
+
+    pub(crate) fn push_decision(&mut self, decision: ScheduleDecision) {
+        self.decisions.push(decision);
+    }
+}
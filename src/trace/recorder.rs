@@ -0,0 +1,274 @@
+use std::any::Any;
+
+use crate::clocks::{ClockStamp, LogicalClocks};
+use crate::keys::{ChannelId, ComponentId};
+use crate::names::ComponentNames;
+use crate::scheduler::ScheduleDecision;
+use crate::simvars::{sim_sched, sim_time};
+use crate::trace::{MsgId, Trace, TraceEventKind, TraceRecord};
+
+/// message wrapped by the simulation while it travels through a channel
+///
+/// messages are only wrapped if tracing or logical clocks are enabled, channels
+/// that inspect messages should look at them through `Envelope::open`
+#[derive(Debug)]
+pub struct Envelope {
+    msg_id: MsgId,
+    stamp: Option<ClockStamp>,
+    message: Box<dyn Any>,
+}
+
+impl Envelope {
+    pub(crate) fn new(msg_id: MsgId, stamp: Option<ClockStamp>, message: Box<dyn Any>) -> Self {
+        Envelope { msg_id, stamp, message }
+    }
+
+    /// returns the message sent by the component
+    pub fn open(message: &dyn Any) -> &dyn Any {
+        match message.downcast_ref::<Envelope>() {
+            Some(envelope) => &*envelope.message,
+            None => message,
+        }
+    }
+}
+
+/// records trace and updates logical clocks on events executed by the simulation
+#[derive(Default)]
+pub(crate) struct Recorder {
+    trace: Option<Trace>,
+    clocks: bool,
+}
+
+impl Recorder {
+    pub fn enable_trace(&mut self) {
+        self.trace.get_or_insert_with(Trace::default);
+    }
+
+    pub fn enable_clocks(&mut self) {
+        self.clocks = true;
+    }
+
+    pub fn trace(&self) -> Option<&Trace> {
+        self.trace.as_ref()
+    }
+
+    fn is_active(&self) -> bool {
+        self.trace.is_some() || self.clocks
+    }
+
+    /// called after the scheduler is initialized
+    pub fn init(&mut self, names: &ComponentNames) {
+        let num_components = names.len();
+        if let Some(trace) = &mut self.trace {
+            trace.set_names(names.clone());
+        }
+
+        if self.clocks {
+            sim_sched().clocks = Some(LogicalClocks::new(num_components));
+        }
+        sim_sched().stamp_messages = self.is_active();
+    }
+
+    pub fn on_decision(&mut self, decision: ScheduleDecision) {
+        if let Some(trace) = &mut self.trace {
+            trace.push_decision(decision);
+        }
+    }
+
+    pub fn on_local(&mut self, component: ComponentId, sender: ComponentId) {
+        if !self.is_active() {
+            return;
+        }
+
+        let stamp = sim_sched().clocks.as_mut().map(|c| c.tick(component));
+        self.record(component, TraceEventKind::Local { sender }, stamp);
+    }
+
+    /// records the messages stamped by the scheduler since the last call,
+    /// call after every component callback so sends follow the event that sent them
+    pub fn on_sent(&mut self) {
+        let sent = std::mem::take(&mut sim_sched().sent);
+
+        if let Some(trace) = &mut self.trace {
+            for msg in sent {
+                let kind = TraceEventKind::Send { channel: msg.channel, msg_id: msg.msg_id };
+                trace.push(TraceRecord { time: msg.time, component: msg.sender, kind, clocks: msg.stamp });
+            }
+        }
+    }
+
+    pub fn on_receive(&mut self, receiver: ComponentId, channel: ChannelId, message: Box<dyn Any>) -> Box<dyn Any> {
+        if !self.is_active() {
+            return message;
+        }
+
+        let (msg_id, sent, message) = match message.downcast::<Envelope>() {
+            Ok(envelope) => (Some(envelope.msg_id), envelope.stamp, envelope.message),
+            Err(message) => (None, None, message),
+        };
+
+        let stamp = sim_sched().clocks.as_mut().map(|c| match &sent {
+            Some(sent) => c.receive(receiver, sent),
+            None => c.tick(receiver),
+        });
+        self.record(receiver, TraceEventKind::Receive { channel, msg_id }, stamp);
+
+        message
+    }
+
+    fn record(&mut self, component: ComponentId, kind: TraceEventKind, clocks: Option<ClockStamp>) {
+        if let Some(trace) = &mut self.trace {
+            trace.push(TraceRecord { time: sim_time(), component, kind, clocks });
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::asynch::process::{AsynchComponent, AsynchProcess};
+    use crate::channels::delay_channel::{DelayChannel, DelayChannelBuilder};
+    use crate::component::{Component, ComponentBase, ComponentBuilder};
+    use crate::sim::Simulation;
+    use crate::trace::TraceEventKind;
+
+    /// node 0 sends a token along the line 0 - 1 - 2
+    struct Relay {
+        base: ComponentBase,
+    }
+
+    impl AsynchProcess for Relay {
+        type Msg = ();
+
+        fn get_sim_base(&self) -> &ComponentBase { &self.base }
+
+        fn get_sim_base_mut(&mut self) -> &mut ComponentBase { &mut self.base }
+
+        fn on_start(&mut self) {
+            if self.id().as_idx() == 0 {
+                self.broadcast(());
+            }
+        }
+
+        fn on_message(&mut self, incoming_channel: ChannelId, _msg: ()) {
+            self.broadcast_except(incoming_channel, ());
+        }
+
+        fn terminate(&mut self) {}
+    }
+
+    struct RelayBuilder;
+
+    impl ComponentBuilder for RelayBuilder {
+        fn build_component(&mut self, id: ComponentId) -> Box<dyn Component> {
+            Box::new(AsynchComponent::new(Relay { base: ComponentBase::new(id) }))
+        }
+    }
+
+    /// traced run of the relay on a line of three nodes with 1ms channels, also used by the exporter tests
+    pub(crate) fn traced_line() -> (Simulation<DelayChannel>, Vec<ComponentId>) {
+        let mut simulation = Simulation::<DelayChannel>::default();
+        let nodes: Vec<ComponentId> = (0..3).map(|_| simulation.add_component(&mut RelayBuilder)).collect();
+        let mut channel_builder = DelayChannelBuilder::with_delay(Duration::from_millis(1));
+        simulation.add_channel(&mut channel_builder, nodes[0], nodes[1]);
+        simulation.add_channel(&mut channel_builder, nodes[1], nodes[2]);
+        simulation.enable_tracing();
+        simulation.enable_logical_clocks();
+
+        simulation.call_init();
+        simulation.run().unwrap();
+        (simulation, nodes)
+    }
+
+    #[test]
+    fn test_clocks_respect_happened_before() {
+        let (simulation, nodes) = traced_line();
+        let records = simulation.trace().unwrap().records();
+        let stamp = |r: &TraceRecord| r.clocks.clone().unwrap();
+
+        let mut pairs = 0;
+        for receive in records {
+            if let TraceEventKind::Receive { msg_id: Some(id), .. } = receive.kind {
+                let send = records.iter().find(|r| matches!(r.kind, TraceEventKind::Send { msg_id, .. } if msg_id == id)).unwrap();
+                assert!(stamp(send).happened_before(&stamp(receive)));
+                assert!(stamp(send).lamport < stamp(receive).lamport);
+                pairs += 1;
+            }
+        }
+        assert_eq!(pairs, 2);
+
+        // the token carries the history of node 0 to node 2
+        let start = |node: ComponentId| records.iter().find(|r| r.component == node).map(stamp).unwrap();
+        let last = records.iter().rev().find(|r| r.component == nodes[2]).map(stamp).unwrap();
+        assert!(start(nodes[0]).happened_before(&last));
+        assert!(start(nodes[0]).is_concurrent(&start(nodes[2])));
+    }
+
+    /// leaves 1 and 2 send to the center 0 at the same time, the center forwards
+    /// the message of leaf 1 to leaf 3 before it receives the message of leaf 2
+    struct Star {
+        base: ComponentBase,
+    }
+
+    impl AsynchProcess for Star {
+        type Msg = usize;
+
+        fn get_sim_base(&self) -> &ComponentBase { &self.base }
+
+        fn get_sim_base_mut(&mut self) -> &mut ComponentBase { &mut self.base }
+
+        fn on_start(&mut self) {
+            if [1, 2].contains(&self.id().as_idx()) {
+                self.send(self.channels()[0], self.id().as_idx());
+            }
+        }
+
+        fn on_message(&mut self, _incoming_channel: ChannelId, msg: usize) {
+            if self.id().as_idx() == 0 && msg == 1 {
+                self.send(self.channels()[2], msg);
+            }
+        }
+
+        fn terminate(&mut self) {}
+    }
+
+    struct StarBuilder;
+
+    impl ComponentBuilder for StarBuilder {
+        fn build_component(&mut self, id: ComponentId) -> Box<dyn Component> {
+            Box::new(AsynchComponent::new(Star { base: ComponentBase::new(id) }))
+        }
+    }
+
+    #[test]
+    fn test_sends_are_stamped_when_sent() {
+        let mut simulation = Simulation::<DelayChannel>::default();
+        let nodes: Vec<ComponentId> = (0..4).map(|_| simulation.add_component(&mut StarBuilder)).collect();
+        let mut channel_builder = DelayChannelBuilder::with_delay(Duration::from_millis(1));
+        for leaf in &nodes[1..] {
+            simulation.add_channel(&mut channel_builder, nodes[0], *leaf);
+        }
+        simulation.enable_tracing();
+        simulation.enable_logical_clocks();
+
+        simulation.call_init();
+        simulation.run().unwrap();
+
+        let records = simulation.trace().unwrap().records();
+        let center: Vec<&TraceRecord> = records.iter().filter(|r| r.component == nodes[0]).collect();
+        assert!(matches!(center[0].kind, TraceEventKind::Local { .. }));
+        assert!(matches!(center[1].kind, TraceEventKind::Receive { .. }));
+        assert!(matches!(center[2].kind, TraceEventKind::Send { .. }));
+        assert!(matches!(center[3].kind, TraceEventKind::Receive { .. }));
+        assert!(center[1..].iter().all(|r| r.time == center[1].time));
+
+        // the forwarded message does not depend on the message of leaf 2
+        let stamp = |r: &TraceRecord| r.clocks.clone().unwrap();
+        let sent_by_leaf_2 = records.iter().find(|r| r.component == nodes[2] && matches!(r.kind, TraceEventKind::Send { .. })).unwrap();
+        let received_by_leaf_3 = records.iter().rev().find(|r| r.component == nodes[3]).unwrap();
+        assert_eq!(stamp(center[2]).vector, [3, 2, 0, 0]);
+        assert!(stamp(sent_by_leaf_2).is_concurrent(&stamp(received_by_leaf_3)));
+    }
+}
@@ -0,0 +1,115 @@
+use std::io;
+use std::io::Write;
+
+use crate::keys::ComponentId;
+use crate::trace::{Trace, TraceEventKind, TraceRecord};
+
+/// regular expression that parses the log written by `Trace::write_shiviz`
+pub const SHIVIZ_REGEX: &str = r"(?<host>\S*) (?<clock>{.*})\n(?<event>.*)";
+
+impl Trace {
+    /// writes the trace as a ShiViz log
+    ///
+    /// the first line holds the parsing regex and the second the (empty) execution delimiter,
+    /// so the file can be loaded into ShiViz as is. requires logical clocks to be enabled
+    pub fn write_shiviz<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        writeln!(w, "{}", SHIVIZ_REGEX)?;
+        writeln!(w)?;
+
+        for record in self.records() {
+            let stamp = record.clocks.as_ref().ok_or_else(|| io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "trace does not have logical clocks, enable them with Simulation::enable_logical_clocks()",
+            ))?;
+
+            let clock: Vec<String> = stamp.vector.iter()
+                .enumerate()
+                .filter(|(_, count)| **count > 0)
+                .map(|(idx, count)| format!("\"{}\":{}", escape_json(&self.host(ComponentId::new(idx))), count))
+                .collect();
+
+            writeln!(w, "{} {{{}}}", self.host(record.component), clock.join(","))?;
+            writeln!(w, "{}", self.describe(record).replace(['\r', '\n'], " "))?;
+        }
+
+        Ok(())
+    }
+
+    /// name of the component without whitespace, the regex ends the host at the first space
+    fn host(&self, component: ComponentId) -> String {
+        self.component_name(component).replace(char::is_whitespace, "_")
+    }
+
+    fn describe(&self, record: &TraceRecord) -> String {
+        let time = record.time.time();
+        match &record.kind {
+            TraceEventKind::Local { sender } => {
+                format!("[{:?}] event from {}", time, self.component_name(*sender))
+            }
+            TraceEventKind::Send { channel, msg_id } => {
+                format!("[{:?}] send msg {} on channel {:?}", time, msg_id, channel)
+            }
+            TraceEventKind::Receive { channel, msg_id: Some(msg_id) } => {
+                format!("[{:?}] receive msg {} on channel {:?}", time, msg_id, channel)
+            }
+            TraceEventKind::Receive { channel, msg_id: None } => {
+                format!("[{:?}] receive msg on channel {:?}", time, channel)
+            }
+        }
+    }
+}
+
+fn escape_json(s: &str) -> String {
+    let mut escaped = String::new();
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::names::ComponentNames;
+    use crate::trace::recorder::test::traced_line;
+
+    #[test]
+    fn test_write_shiviz() {
+        let (simulation, _) = traced_line();
+
+        let mut out = Vec::new();
+        simulation.trace().unwrap().write_shiviz(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+
+        assert_eq!(lines[0], SHIVIZ_REGEX);
+        assert_eq!(lines[1], "");
+        let hosts: Vec<&str> = lines[2..].iter().step_by(2).copied().collect();
+        assert_eq!(hosts, [
+            r#"c0 {"c0":1}"#,
+            r#"c0 {"c0":2}"#,
+            r#"c1 {"c1":1}"#,
+            r#"c2 {"c2":1}"#,
+            r#"c1 {"c0":2,"c1":2}"#,
+            r#"c1 {"c0":2,"c1":3}"#,
+            r#"c2 {"c0":2,"c1":3,"c2":2}"#,
+        ]);
+    }
+
+    #[test]
+    fn test_names_are_escaped() {
+        let mut names = ComponentNames::default();
+        names.push(Some(String::from("net \"a\"\\b")), String::from("A"));
+        let mut trace = Trace::default();
+        trace.set_names(names);
+
+        let host = trace.host(ComponentId::new(0));
+        assert_eq!(host, r#"net_"a"\b"#);
+        assert_eq!(escape_json(&host), r#"net_\"a\"\\b"#);
+    }
+}
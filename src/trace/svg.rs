@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+use std::io;
+use std::io::Write;
+
+use crate::keys::ComponentId;
+use crate::trace::{MsgId, Trace, TraceEventKind};
+
+const MARGIN_LEFT: f64 = 80.0;
+const MARGIN_RIGHT: f64 = 40.0;
+const MARGIN_Y: f64 = 40.0;
+const WIDTH: f64 = 1000.0;
+const ROW_HEIGHT: f64 = 50.0;
+
+impl Trace {
+    /// writes a space-time diagram of the trace as a self-contained svg
+    ///
+    /// every component has a horizontal line with time running from left to right,
+    /// messages are arrows from the send to the receive event and local events are dots
+    pub fn write_svg<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        let num_components = self.num_components().max(
+            self.records().iter().map(|r| r.component.as_idx() + 1).max().unwrap_or(0)
+        );
+        let end_time = self.records().iter()
+            .map(|r| r.time.time().as_secs_f64())
+            .fold(0.0, f64::max);
+
+        let x = |secs: f64| {
+            if end_time == 0.0 { MARGIN_LEFT } else { MARGIN_LEFT + secs / end_time * WIDTH }
+        };
+        let y = |component: ComponentId| MARGIN_Y + component.as_idx() as f64 * ROW_HEIGHT;
+
+        let width = MARGIN_LEFT + WIDTH + MARGIN_RIGHT;
+        let height = 2.0 * MARGIN_Y + num_components.saturating_sub(1) as f64 * ROW_HEIGHT;
+
+        writeln!(w, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" font-family="monospace" font-size="12">"#, width, height)?;
+        writeln!(w, r#"<defs><marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="6" markerHeight="6" orient="auto-start-reverse"><path d="M 0 0 L 10 5 L 0 10 z" fill="steelblue"/></marker></defs>"#)?;
+
+        // component lines
+        for idx in 0..num_components {
+            let component = ComponentId::new(idx);
+            writeln!(w, r#"<text x="{}" y="{}" text-anchor="end" dominant-baseline="middle">{}</text>"#,
+                     MARGIN_LEFT - 10.0, y(component), escape_xml(&self.component_name(component)))?;
+            writeln!(w, r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="black"/>"#,
+                     MARGIN_LEFT, y(component), MARGIN_LEFT + WIDTH, y(component))?;
+        }
+
+        writeln!(w, r#"<text x="{}" y="{}" text-anchor="end">{:?}</text>"#,
+                 MARGIN_LEFT + WIDTH, height - 10.0, std::time::Duration::from_secs_f64(end_time))?;
+
+        // messages
+        let mut sends: HashMap<MsgId, (f64, f64)> = HashMap::new();
+
+        for record in self.records() {
+            let px = x(record.time.time().as_secs_f64());
+            let py = y(record.component);
+
+            match &record.kind {
+                TraceEventKind::Local { .. } => {
+                    writeln!(w, r#"<circle cx="{:.2}" cy="{:.2}" r="4" fill="black"/>"#, px, py)?;
+                }
+                TraceEventKind::Send { msg_id, .. } => {
+                    sends.insert(*msg_id, (px, py));
+                }
+                TraceEventKind::Receive { msg_id, .. } => {
+                    match msg_id.and_then(|id| sends.get(&id)) {
+                        Some((sx, sy)) => {
+                            writeln!(w, r#"<line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" stroke="steelblue" marker-end="url(#arrow)"/>"#,
+                                     sx, sy, px, py)?;
+                        }
+                        None => {
+                            writeln!(w, r#"<circle cx="{:.2}" cy="{:.2}" r="4" fill="none" stroke="steelblue"/>"#, px, py)?;
+                        }
+                    }
+                }
+            }
+        }
+
+        writeln!(w, "</svg>")
+    }
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::trace::recorder::test::traced_line;
+
+    #[test]
+    fn test_one_arrow_per_message() {
+        let (simulation, _) = traced_line();
+
+        let mut out = Vec::new();
+        simulation.trace().unwrap().write_svg(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        assert!(out.starts_with("<svg") && out.ends_with("</svg>\n"));
+        assert_eq!(out.matches(r#"marker-end="url(#arrow)""#).count(), 2);
+        assert_eq!(out.matches(r#"<line "#).count(), 3 + 2);
+    }
+
+    #[test]
+    fn test_names_are_escaped() {
+        assert_eq!(escape_xml(r#"<a & "b's">"#), "&lt;a &amp; &quot;b&apos;s&quot;&gt;");
+    }
+}